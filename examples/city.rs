@@ -4,7 +4,7 @@ extern crate pathtracer;
 use pathtracer::*;
 use std::path::Path;
 
-fn main() -> Result<(), image::ImageError> {
+fn main() -> Result<(), Error> {
     let mut pos = Vec::new();
     let city_size = 30;
     let spread = 15;
@@ -13,7 +13,7 @@ fn main() -> Result<(), image::ImageError> {
         for x in 0..city_size * 2 {
             let mut node = node!(spread * x, spread * y);
 
-            node.color = tools::seed_rgb((city_size * x + spread * y) as u64);
+            node.color = Some(tools::seed_rgb((city_size * x + spread * y) as u64));
             pos.push(node);
         }
     }
@@ -7,7 +7,7 @@ use pathtracer::{map::gif::*, *};
 use std::env;
 
 fn main() -> std::io::Result<()> {
-    let mut gif = Gif::new("out.gif", 150, 200);
+    let mut gif = Gif::new("out.gif", 150, 200)?;
     let mut balls = Node::from_list(&[
         (50, 55),
         (70, 70),
@@ -22,7 +22,7 @@ fn main() -> std::io::Result<()> {
     ]);
 
     for n in balls.iter_mut() {
-        n.color = image::Rgb([255, 50, 50]);
+        n.color = Some(image::Rgba([255, 50, 50, 255]));
     }
 
     gif.cycle(2, balls);
@@ -33,7 +33,7 @@ fn main() -> std::io::Result<()> {
     let nodes = Node::from_file(&args[1])?;
     let mut nodes = Node::linked_list(nodes);
     for n in nodes.iter_mut() {
-        n.color = image::Rgb([0, 100, 0]);
+        n.color = Some(image::Rgba([0, 100, 0, 255]));
     }
 
     gif.cycle(1, nodes);
@@ -5,7 +5,8 @@ Extra functionality for coordinates.
 extern crate pythagoras;
 extern crate rand;
 
-use super::{tools::roll, Coordinate};
+use super::{tools::roll_with, Coordinate, Point};
+use rand::Rng;
 use std::{cmp::Ordering, f64};
 
 /**
@@ -16,7 +17,7 @@ Constructs a vector of generic structs from a given list convered to Coordinates
 examples/city.rs
 
 */
-pub fn from_list<T>(list: &[(i16, i16)], get: &dyn Fn(Coordinate, usize) -> T) -> Vec<T> {
+pub fn from_list<T>(list: &[(i32, i32)], get: &dyn Fn(Coordinate, usize) -> T) -> Vec<T> {
     let mut result: Vec<T> = Vec::new();
     for (i, &(x, y)) in list.iter().enumerate() {
         result.push(get(Coordinate::new(x, y), i));
@@ -28,9 +29,16 @@ pub fn from_list<T>(list: &[(i16, i16)], get: &dyn Fn(Coordinate, usize) -> T) -
 Constructs a randomly positioned coordinate.
 */
 pub fn gen() -> Coordinate {
+    gen_with(&mut rand::thread_rng())
+}
+
+/**
+Constructs a coordinate positioned by the given random number generator.
+*/
+pub fn gen_with<R: Rng + ?Sized>(rng: &mut R) -> Coordinate {
     Coordinate {
-        x: rand::random::<i16>(),
-        y: rand::random::<i16>(),
+        x: i32::from(rng.gen::<i16>()),
+        y: i32::from(rng.gen::<i16>()),
     }
 }
 
@@ -43,7 +51,7 @@ Calculate a coordinate based on another coordinate and a function.
 ```
 # use pathtracer::{coordinate::*, Coordinate};
 let c1 = Coordinate::new(0, 0);
-let f = |i: usize| -> Coordinate { Coordinate::new(i as i16, i as i16) };
+let f = |i: usize| -> Coordinate { Coordinate::new(i as i32, i as i32) };
 let c2 = calc(c1, 5, &f);
 assert_eq!(c2, Coordinate { x: 5, y: 5 });
 ```
@@ -72,7 +80,7 @@ assert_eq!(difference, (100, 100));
 # }
 ```
 */
-pub fn diff(c1: Coordinate, c2: Coordinate) -> (i16, i16) {
+pub fn diff(c1: Coordinate, c2: Coordinate) -> (i32, i32) {
     let c = (c1 - c2).abs();
     (c.x, c.y)
 }
@@ -115,6 +123,17 @@ pub fn gen_within_radius(coord: Coordinate, radius: u32) -> Coordinate {
     gen_radius(coord, 0, radius)
 }
 
+/**
+Generate a Coordinate within a radius of the given Coordinate, placed by the given random number generator.
+*/
+pub fn gen_within_radius_with<R: Rng + ?Sized>(
+    rng: &mut R,
+    coord: Coordinate,
+    radius: u32,
+) -> Coordinate {
+    gen_radius_with(rng, coord, 0, radius)
+}
+
 /**
 Generate a Coordinate from a given Coordinate and randomly places it within
 a min and max radius.
@@ -129,20 +148,72 @@ let c2 = coordinate::gen_radius(c1, 50, 100);
 ```
 */
 pub fn gen_radius(coord: Coordinate, min: u32, max: u32) -> Coordinate {
+    gen_radius_with(&mut rand::thread_rng(), coord, min, max)
+}
+
+/**
+Generate a Coordinate between a min and max radius of the given Coordinate, placed by the given
+random number generator.
+
+The same seed gives the same Coordinate.
+
+
+## Examples
+
+```
+# use pathtracer::{coordinate, Coordinate};
+use rand::{rngs::StdRng, SeedableRng};
+let c1 = Coordinate::new(0, 0);
+let a = coordinate::gen_radius_with(&mut StdRng::seed_from_u64(3), c1, 50, 100);
+let b = coordinate::gen_radius_with(&mut StdRng::seed_from_u64(3), c1, 50, 100);
+assert_eq!(a, b);
+```
+*/
+pub fn gen_radius_with<R: Rng + ?Sized>(
+    rng: &mut R,
+    coord: Coordinate,
+    min: u32,
+    max: u32,
+) -> Coordinate {
+    let point = gen_radius_point_with(rng, Point::from(coord), min, max);
+    Coordinate::new(point.x as i32, point.y as i32)
+}
+
+/**
+Generate a Point between a min and max radius of the given Point, placed by the given random number
+generator.
+
+Unlike gen_radius_with, the position is not truncated to a pixel, which is what Nodes are placed with.
+
+
+## Examples
+
+```
+# use pathtracer::{coordinate, Point};
+use rand::{rngs::StdRng, SeedableRng};
+let p = coordinate::gen_radius_point_with(&mut StdRng::seed_from_u64(3), Point::new(0.0, 0.0), 50, 100);
+let r = p.x.hypot(p.y);
+assert!((50.0..=100.0).contains(&r));
+assert!(p.x.fract() != 0.0 || p.y.fract() != 0.0);
+```
+*/
+pub fn gen_radius_point_with<R: Rng + ?Sized>(
+    rng: &mut R,
+    point: Point,
+    min: u32,
+    max: u32,
+) -> Point {
     // Randomly gets the radius of the circle.
-    let r = f64::from(roll(min, max));
+    let r = f64::from(roll_with(rng, min, max));
 
     // gets a point on the circle's circumference.
     let circle = |a: f64, b: f64| a + r * b;
 
     // Gets a random angle.
-    let angle = roll(0u32, 3600u32);
+    let angle = roll_with(rng, 0u32, 3600u32);
     let a: f64 = f64::consts::PI * 0.001 * f64::from(angle);
 
-    let x = circle(f64::from(coord.x), a.cos()) as i16;
-    let y = circle(f64::from(coord.y), a.sin()) as i16;
-
-    Coordinate { x, y }
+    Point::new(circle(point.x, a.cos()), circle(point.y, a.sin()))
 }
 
 /**
@@ -155,11 +226,11 @@ If deg == 0.0, no rotation occurs.
 
 ```
 # #[macro_use] extern crate pathtracer;
-# use pathtracer::{coordinate::*, Coordinate, Node};
+# use pathtracer::{coordinate::*, Coordinate, Location, Node};
 # fn main() {
 let mut v = vec![node!(0, 100)];
 rotate_around_axis(coordinate!(), &mut v, 90.0);
-assert_eq!(v.remove(0).geo, Coordinate::new(100, 0));
+assert_eq!(v.remove(0).position(), Coordinate::new(100, 0));
 # }
 ```
 
@@ -174,14 +245,15 @@ pub fn rotate_around_axis(axis: Coordinate, points: &mut [super::Node], deg: f64
         return;
     }
 
+    let axis = Point::from(axis);
     for p in points.iter_mut() {
-        let radius = f64::from(distance(axis, p.geo));
         let diff = p.geo - axis;
-        let base = f64::from(diff.x).atan2(f64::from(diff.y));
-        let angle = base + (deg * f64::consts::PI / 180.0);
+        let radius = diff.x.hypot(diff.y);
+        let base = diff.x.atan2(diff.y);
+        let angle = base + deg.to_radians();
 
-        p.geo.y = axis.y + f64::round(angle.cos() * radius) as i16;
-        p.geo.x = axis.x + f64::round(angle.sin() * radius) as i16;
+        p.geo.y = axis.y + angle.cos() * radius;
+        p.geo.x = axis.x + angle.sin() * radius;
     }
 }
 
@@ -244,9 +316,42 @@ impl std::ops::Mul for Coordinate {
     }
 }
 
+impl std::ops::Add for Point {
+    type Output = Point;
+
+    fn add(self, other: Point) -> Point {
+        Point::new(self.x + other.x, self.y + other.y)
+    }
+}
+
+impl std::ops::Sub for Point {
+    type Output = Point;
+
+    fn sub(self, other: Point) -> Point {
+        Point::new(self.x - other.x, self.y - other.y)
+    }
+}
+
+impl std::ops::AddAssign for Point {
+    fn add_assign(&mut self, other: Point) {
+        self.x += other.x;
+        self.y += other.y;
+    }
+}
+
+impl std::ops::SubAssign for Point {
+    fn sub_assign(&mut self, other: Point) {
+        self.x -= other.x;
+        self.y -= other.y;
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::{super::Node, *};
+    use super::{
+        super::{Location, Node},
+        *,
+    };
 
     #[test]
     fn test_eq() {
@@ -290,7 +395,7 @@ mod tests {
         for deg in 0..10 {
             let mut v = vec![Node::new("", Coordinate::new(0, 100))];
             rotate_around_axis(c1, &mut v, f64::from(deg * 360));
-            assert_eq!(v.remove(0).geo, Coordinate::new(0, 100));
+            assert_eq!(v.remove(0).position(), Coordinate::new(0, 100));
         }
     }
 
@@ -301,7 +406,19 @@ mod tests {
         for _ in 0..4 {
             rotate_around_axis(c1, &mut v, 90.0);
         }
-        assert_eq!(v.remove(0).geo, Coordinate::new(0, 100));
+        assert_eq!(v.remove(0).position(), Coordinate::new(0, 100));
+    }
+
+    #[test]
+    fn test_rotate_around_no_drift() {
+        let c1 = Coordinate::new(0, 0);
+        let mut v = vec![Node::new("", Coordinate::new(37, 100))];
+        for _ in 0..3600 {
+            rotate_around_axis(c1, &mut v, 1.0);
+        }
+        let geo = v.remove(0).geo;
+        assert!((geo.x - 37.0).abs() < 1e-6, "{}", geo);
+        assert!((geo.y - 100.0).abs() < 1e-6, "{}", geo);
     }
 
     #[test]
@@ -309,7 +426,7 @@ mod tests {
         let c1 = Coordinate::new(0, 0);
         let mut v = vec![Node::new("", Coordinate::new(0, 100))];
         rotate_around_axis(c1, &mut v, 90.0);
-        assert_eq!(v.remove(0).geo, Coordinate::new(100, 0));
+        assert_eq!(v.remove(0).position(), Coordinate::new(100, 0));
     }
 
     #[test]
@@ -317,7 +434,7 @@ mod tests {
         let c1 = Coordinate::new(0, 0);
         let mut v = vec![Node::new("", Coordinate::new(0, 100))];
         rotate_around_axis(c1, &mut v, 180.0);
-        assert_eq!(v.remove(0).geo, Coordinate::new(0, -100));
+        assert_eq!(v.remove(0).position(), Coordinate::new(0, -100));
     }
 
     #[test]
@@ -325,7 +442,7 @@ mod tests {
         let c1 = Coordinate::new(0, 0);
         let mut v = vec![Node::new("", Coordinate::new(0, 100))];
         rotate_around_axis(c1, &mut v, 270.0);
-        assert_eq!(v.remove(0).geo, Coordinate::new(-100, 0));
+        assert_eq!(v.remove(0).position(), Coordinate::new(-100, 0));
     }
 
     #[test]
@@ -333,7 +450,7 @@ mod tests {
         let c1 = Coordinate::new(100, 100);
         let mut v = vec![Node::new("", Coordinate::new(200, 100))];
         rotate_around_axis(c1, &mut v, 90.0);
-        assert_eq!(v.remove(0).geo, Coordinate::new(100, 0));
+        assert_eq!(v.remove(0).position(), Coordinate::new(100, 0));
     }
 
     #[test]
@@ -341,7 +458,7 @@ mod tests {
         let c1 = Coordinate::new(99, 99);
         let mut v = vec![Node::new("", Coordinate::new(199, 99))];
         rotate_around_axis(c1, &mut v, 90.0);
-        assert_eq!(v.remove(0).geo, Coordinate::new(99, -1));
+        assert_eq!(v.remove(0).position(), Coordinate::new(99, -1));
     }
 
     #[test]
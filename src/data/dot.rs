@@ -0,0 +1,1060 @@
+/*!
+Reads and writes Nodes in the Graphviz DOT language.
+
+Nodes are written with their position as `pos`, and their color, label and shape, if set, as `color`, `label` and `shape`.
+Rings are written as `doublecircle`, and custom shapes are left out.
+Links become edges, with the weight as `weight`, a solid Paint as `color`, the width as `penwidth`,
+the Arrow as `arrowhead` and the Stroke as `style`. Gradients are not written, as DOT has no equivalent.
+
+In Graphviz `splines` is an attribute of the whole graph, so when all links share an EdgeStyle it is written as the graph's `splines`.
+Each edge also keeps its own EdgeStyle in the non-standard `pathtracer_splines` attribute, using the same values,
+and Bezier curves keep their curvature in the non-standard `pathtracer_curvature`. Other tools ignore both.
+Bezier curves are written as `spline` splines, and Orthogonal edges as `polyline` splines.
+When reading, edges without `pathtracer_splines` take the EdgeStyle of the graph's `splines`.
+
+```text
+digraph {
+    "Home" [pos="10,20", color="#ff0000"];
+    9 [pos="30,40", pathtracer_hash=9];
+    splines=curved;
+    "Home" -> 9 [pathtracer_splines=curved, weight=5];
+}
+```
+
+Nodes are written with their label as the id, as the label is the only name a Node keeps.
+Nodes without a label, or sharing one with a Node before them, are written using their hash as the id.
+Whenever hashing the id would not give back the hash of the Node, it is kept in the non-standard `pathtracer_hash` attribute.
+
+When reading, ids are hashed the same way as Node::new, so `a -> b` links the Nodes named "a" and "b",
+and Network::get finds them by their names. The id becomes the label of the Node, unless it has a
+`label` attribute or the id is the hash given in `pathtracer_hash`.
+Nodes without a `pos` attribute are laid out by a ForceDirected layout around the positioned ones, which stay in place.
+Subgraphs are flattened, and edges in an undirected graph link both ways.
+ */
+
+use super::super::{map::theme::Paint, *};
+use std::{
+    collections::{HashMap, HashSet},
+    fs::File,
+    io::prelude::*,
+};
+
+/**
+A lexical element with the line it was found on.
+ */
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Id(String),
+    Edge(bool),
+    Symbol(char),
+}
+
+/**
+Attributes in the order they were given, with the line they were found on.
+ */
+type Attributes = Vec<(String, String, usize)>;
+
+/**
+Default attributes set with node and edge statements, scoped to their subgraph.
+ */
+#[derive(Clone, Default)]
+struct Defaults {
+    node: Attributes,
+    edge: Attributes,
+}
+
+struct Edge {
+    from: String,
+    to: String,
+    attributes: Attributes,
+}
+
+struct Parser {
+    tokens: Vec<(Token, usize)>,
+    pos: usize,
+    directed: bool,
+    defaults: Defaults,
+    nodes: Vec<(String, Attributes)>,
+    index: HashMap<String, usize>,
+    edges: Vec<Edge>,
+    mentions: Vec<String>,
+    splines: Option<String>,
+}
+
+/**
+Writes the Nodes to a file.
+
+
+## Errors
+
+Could not create or write to the file.
+ */
+pub fn save(path: &str, nodes: &[Node]) -> Result<(), Error> {
+    let mut file = File::create(path)?;
+    write(&mut file, nodes)
+}
+
+/**
+Reads Nodes from a file.
+
+
+## Errors
+
+Could not open or read the file.
+
+Error::ParseError if the content is not a valid graph.
+ */
+pub fn load(path: &str) -> Result<Vec<Node>, Error> {
+    let mut contents = String::new();
+    File::open(path)?.read_to_string(&mut contents)?;
+    read(&contents)
+}
+
+/**
+Writes the Nodes as a directed graph to any writer.
+
+
+## Errors
+
+Could not write.
+ */
+pub fn write<W: Write>(writer: &mut W, nodes: &[Node]) -> Result<(), Error> {
+    let mut ids: HashMap<u64, String> = HashMap::new();
+    let mut used = HashSet::new();
+    writeln!(writer, "digraph {{")?;
+    for node in nodes {
+        let name = node.label.as_ref().filter(|l| used.insert(l.as_str()));
+        let id = name.map_or_else(|| node.hash.to_string(), |l| quote(l));
+        write!(writer, "    {} [pos=\"{},{}\"", id, node.geo.x, node.geo.y)?;
+        if let Some(color) = node.color {
+            write!(writer, ", color=\"{}\"", tools::to_hex(color))?;
+        }
+        if let (Some(label), None) = (&node.label, name) {
+            write!(writer, ", label={}", quote(label))?;
+        }
+        let named = name.map_or_else(|| node.hash.to_string(), |l| l.to_string());
+        if data::calculate_hash(&named.as_str()) != node.hash {
+            write!(writer, ", pathtracer_hash={}", node.hash)?;
+        }
+        if let Some(name) = node.shape.as_ref().and_then(shape_name) {
+            write!(writer, ", shape={}", name)?;
+        }
+        writeln!(writer, "];")?;
+        ids.insert(node.hash, id);
+    }
+    let id = |hash: u64| ids.get(&hash).cloned().unwrap_or_else(|| hash.to_string());
+    let mut names = nodes
+        .iter()
+        .flat_map(|n| n.links().iter().map(|l| splines_name(l.style)));
+    if let Some(first) = names.next() {
+        if names.all(|name| name == first) {
+            writeln!(writer, "    splines={};", first)?;
+        }
+    }
+    for node in nodes {
+        for link in node.links() {
+            write!(
+                writer,
+                "    {} -> {} [pathtracer_splines={}",
+                id(link.f),
+                id(link.t),
+                splines_name(link.style)
+            )?;
+            if let Some(weight) = link.weight {
+                write!(writer, ", weight={}", weight)?;
+            }
+            if let Some(Paint::Solid(color)) = link.paint {
+                write!(writer, ", color=\"{}\"", tools::to_hex(color))?;
+            }
+            if let Some(width) = link.width {
+                write!(writer, ", penwidth={}", width)?;
+            }
+            if let Some(arrow) = link.arrow {
+                write!(writer, ", arrowhead={}", arrowhead_name(arrow))?;
+            }
+            if let EdgeStyle::Bezier(curvature) = link.style {
+                write!(writer, ", pathtracer_curvature={}", curvature)?;
+            }
+            match link.stroke {
+                Stroke::Solid => (),
+                Stroke::Dashed => write!(writer, ", style=dashed")?,
+                Stroke::Dotted => write!(writer, ", style=dotted")?,
+            }
+            writeln!(writer, "];")?;
+        }
+    }
+    writeln!(writer, "}}")?;
+    Ok(())
+}
+
+/**
+Returns the id quoted, with quotes and backslashes in it escaped.
+ */
+fn quote(id: &str) -> String {
+    format!("\"{}\"", id.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/**
+Returns the Nodes as a DOT graph.
+
+
+## Examples
+
+```
+# use pathtracer::{data::dot, *};
+let nodes = Node::linked_list(Node::from_list(&[(0, 0), (10, 10)]));
+let content = dot::to_string(&nodes);
+assert!(content.starts_with("digraph {"));
+assert!(content.contains("pos=\"10,10\""));
+assert!(content.contains(" -> "));
+```
+ */
+pub fn to_string(nodes: &[Node]) -> String {
+    let mut buffer = Vec::new();
+    // Writing to a Vec can not fail.
+    let _ = write(&mut buffer, nodes);
+    String::from_utf8_lossy(&buffer).into_owned()
+}
+
+/**
+Parses Nodes and their links from a DOT graph.
+
+
+## Examples
+
+```
+# use pathtracer::{data::dot, *};
+let content = "digraph cities {
+    a [pos=\"0,0\", color=\"#ff0000\"];
+    b [pos=\"100,50\"];
+    a -> b -> c [weight=3];
+}";
+let nodes = dot::read(content).unwrap();
+assert_eq!(nodes.len(), 3);
+assert_eq!(nodes[0], Node::new("a", Coordinate::new(0, 0)));
+assert_eq!(nodes[0].color, Some(image::Rgba([255, 0, 0, 255])));
+assert_eq!(nodes[0].hl(0).unwrap().t, nodes[1].hash);
+assert_eq!(nodes[1].hl(0).unwrap().weight, Some(3));
+```
+
+
+## Errors
+
+Error::ParseError with the line of the first invalid statement.
+ */
+pub fn read(content: &str) -> Result<Vec<Node>, Error> {
+    let mut parser = Parser {
+        tokens: tokenize(content)?,
+        pos: 0,
+        directed: true,
+        defaults: Defaults::default(),
+        nodes: Vec::new(),
+        index: HashMap::new(),
+        edges: Vec::new(),
+        mentions: Vec::new(),
+        splines: None,
+    };
+    parser.graph()?;
+    parser.build()
+}
+
+/**
+Splits the content in to tokens, skipping whitespace and comments.
+ */
+fn tokenize(content: &str) -> Result<Vec<(Token, usize)>, Error> {
+    let mut tokens = Vec::new();
+    let mut chars = content.chars().peekable();
+    let mut line = 1;
+    let err = |line: usize, reason: String| Error::ParseError { line, reason };
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\n' => line += 1,
+            c if c.is_whitespace() => (),
+            '#' => {
+                while chars.peek().is_some_and(|c| *c != '\n') {
+                    chars.next();
+                }
+            }
+            '/' if chars.peek() == Some(&'/') => {
+                while chars.peek().is_some_and(|c| *c != '\n') {
+                    chars.next();
+                }
+            }
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+                let start = line;
+                let mut last = ' ';
+                loop {
+                    match chars.next() {
+                        Some('/') if last == '*' => break,
+                        Some(c) => {
+                            if c == '\n' {
+                                line += 1;
+                            }
+                            last = c;
+                        }
+                        None => return Err(err(start, "unterminated comment".to_string())),
+                    }
+                }
+            }
+            '"' => {
+                let start = line;
+                let mut id = String::new();
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') if matches!(chars.peek(), Some('"') | Some('\\')) => {
+                            id.push(chars.next().unwrap())
+                        }
+                        Some(c) => {
+                            if c == '\n' {
+                                line += 1;
+                            }
+                            id.push(c);
+                        }
+                        None => return Err(err(start, "unterminated string".to_string())),
+                    }
+                }
+                tokens.push((Token::Id(id), start));
+            }
+            '<' => {
+                let start = line;
+                let mut id = String::new();
+                let mut depth = 1;
+                while depth > 0 {
+                    match chars.next() {
+                        Some(c) => {
+                            match c {
+                                '<' => depth += 1,
+                                '>' => depth -= 1,
+                                '\n' => line += 1,
+                                _ => (),
+                            }
+                            if depth > 0 {
+                                id.push(c);
+                            }
+                        }
+                        None => return Err(err(start, "unterminated html string".to_string())),
+                    }
+                }
+                tokens.push((Token::Id(id), start));
+            }
+            '-' if chars.peek() == Some(&'>') || chars.peek() == Some(&'-') => {
+                let directed = chars.next() == Some('>');
+                tokens.push((Token::Edge(directed), line));
+            }
+            '{' | '}' | '[' | ']' | '=' | ';' | ',' | ':' => tokens.push((Token::Symbol(c), line)),
+            c if c.is_alphanumeric() || c == '_' || c == '.' || c == '-' => {
+                let mut id = c.to_string();
+                while let Some(&c) = chars.peek() {
+                    if !(c.is_alphanumeric() || c == '_' || c == '.') {
+                        break;
+                    }
+                    id.push(c);
+                    chars.next();
+                }
+                tokens.push((Token::Id(id), line));
+            }
+            c => return Err(err(line, format!("unexpected character '{}'", c))),
+        }
+    }
+    Ok(tokens)
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos).map(|(t, _)| t)
+    }
+
+    fn line(&self) -> usize {
+        self.tokens
+            .get(self.pos)
+            .or_else(|| self.tokens.last())
+            .map_or(1, |(_, line)| *line)
+    }
+
+    fn err(&self, reason: String) -> Error {
+        Error::ParseError {
+            line: self.line(),
+            reason,
+        }
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).map(|(t, _)| t.clone());
+        self.pos += 1;
+        token
+    }
+
+    /**
+    Consumes the token if it is the given symbol.
+     */
+    fn accept(&mut self, symbol: char) -> bool {
+        if self.peek() == Some(&Token::Symbol(symbol)) {
+            self.pos += 1;
+            return true;
+        }
+        false
+    }
+
+    fn expect(&mut self, symbol: char) -> Result<(), Error> {
+        if self.accept(symbol) {
+            return Ok(());
+        }
+        Err(self.err(format!("expected '{}', found {}", symbol, self.found())))
+    }
+
+    fn id(&mut self) -> Result<String, Error> {
+        match self.peek() {
+            Some(Token::Id(_)) => match self.next() {
+                Some(Token::Id(id)) => Ok(id),
+                _ => unreachable!(),
+            },
+            _ => Err(self.err(format!("expected an id, found {}", self.found()))),
+        }
+    }
+
+    fn found(&self) -> String {
+        match self.peek() {
+            Some(Token::Id(id)) => format!("'{}'", id),
+            Some(Token::Edge(true)) => "'->'".to_string(),
+            Some(Token::Edge(false)) => "'--'".to_string(),
+            Some(Token::Symbol(c)) => format!("'{}'", c),
+            None => "end of file".to_string(),
+        }
+    }
+
+    /**
+    Returns true if the next token is the keyword, which are case insensitive.
+     */
+    fn keyword(&self, keyword: &str) -> bool {
+        matches!(self.peek(), Some(Token::Id(id)) if id.eq_ignore_ascii_case(keyword))
+    }
+
+    fn graph(&mut self) -> Result<(), Error> {
+        if self.keyword("strict") {
+            self.pos += 1;
+        }
+        if self.keyword("digraph") {
+            self.directed = true;
+        } else if self.keyword("graph") {
+            self.directed = false;
+        } else {
+            return Err(self.err(format!(
+                "expected 'graph' or 'digraph', found {}",
+                self.found()
+            )));
+        }
+        self.pos += 1;
+        if let Some(Token::Id(_)) = self.peek() {
+            self.pos += 1;
+        }
+        self.expect('{')?;
+        self.statements()?;
+        if self.peek().is_some() {
+            return Err(self.err(format!("expected end of file, found {}", self.found())));
+        }
+        Ok(())
+    }
+
+    /**
+    Parses statements until the closing brace, which is consumed.
+     */
+    fn statements(&mut self) -> Result<(), Error> {
+        while !self.accept('}') {
+            if self.peek().is_none() {
+                return Err(self.err("expected '}', found end of file".to_string()));
+            }
+            self.statement()?;
+            self.accept(';');
+        }
+        Ok(())
+    }
+
+    fn statement(&mut self) -> Result<(), Error> {
+        for kind in ["graph", "node", "edge"].iter() {
+            if self.keyword(kind) {
+                self.pos += 1;
+                let attributes = self.attributes()?;
+                match *kind {
+                    "node" => self.defaults.node.extend(attributes),
+                    "edge" => self.defaults.edge.extend(attributes),
+                    _ => self.graph_attributes(attributes),
+                }
+                return Ok(());
+            }
+        }
+
+        if let (Some(Token::Id(_)), Some((Token::Symbol('='), _))) =
+            (self.peek(), self.tokens.get(self.pos + 1))
+        {
+            let line = self.line();
+            let key = self.id()?;
+            self.pos += 1;
+            let value = self.id()?;
+            self.graph_attributes(vec![(key, value, line)]);
+            return Ok(());
+        }
+
+        let mut operands = vec![self.operand()?];
+        while let Some(Token::Edge(directed)) = self.peek() {
+            if *directed != self.directed {
+                return Err(self.err(format!(
+                    "{} used in a {}",
+                    self.found(),
+                    if self.directed { "digraph" } else { "graph" }
+                )));
+            }
+            self.pos += 1;
+            operands.push(self.operand()?);
+        }
+
+        let attributes = self.attributes()?;
+        if operands.len() == 1 {
+            // A node statement, subgraphs have no attributes of their own.
+            if let [id] = operands[0].as_slice() {
+                let i = self.index[id];
+                self.nodes[i].1.extend(attributes);
+            }
+            return Ok(());
+        }
+
+        let mut edge = self.defaults.edge.clone();
+        edge.extend(attributes);
+        for pair in operands.windows(2) {
+            for from in &pair[0] {
+                for to in &pair[1] {
+                    self.edges.push(Edge {
+                        from: from.clone(),
+                        to: to.clone(),
+                        attributes: edge.clone(),
+                    });
+                    if !self.directed {
+                        self.edges.push(Edge {
+                            from: to.clone(),
+                            to: from.clone(),
+                            attributes: edge.clone(),
+                        });
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /**
+    Keeps the graph attributes which affect the Nodes, other attributes such as rankdir=LR are ignored.
+     */
+    fn graph_attributes(&mut self, attributes: Attributes) {
+        for (key, value, _) in attributes {
+            if key == "splines" {
+                self.splines = Some(value);
+            }
+        }
+    }
+
+    /**
+    Parses a node id or a subgraph, returning the ids of all Nodes it refers to.
+     */
+    fn operand(&mut self) -> Result<Vec<String>, Error> {
+        if self.keyword("subgraph") || self.peek() == Some(&Token::Symbol('{')) {
+            if self.keyword("subgraph") {
+                self.pos += 1;
+                if let Some(Token::Id(_)) = self.peek() {
+                    self.pos += 1;
+                }
+            }
+            self.expect('{')?;
+            let start = self.mentions.len();
+            let defaults = self.defaults.clone();
+            self.statements()?;
+            self.defaults = defaults;
+
+            let mut ids = self.mentions[start..].to_vec();
+            ids.sort_by_key(|id| self.index[id]);
+            ids.dedup();
+            return Ok(ids);
+        }
+
+        let id = self.id()?;
+        // Ports only affect where edges attach in Graphviz.
+        while self.accept(':') {
+            self.id()?;
+        }
+        if !self.index.contains_key(&id) {
+            self.index.insert(id.clone(), self.nodes.len());
+            self.nodes.push((id.clone(), self.defaults.node.clone()));
+        }
+        self.mentions.push(id.clone());
+        Ok(vec![id])
+    }
+
+    /**
+    Parses any number of attribute lists.
+     */
+    fn attributes(&mut self) -> Result<Attributes, Error> {
+        let mut attributes = Attributes::new();
+        while self.accept('[') {
+            while !self.accept(']') {
+                let line = self.line();
+                let key = self.id()?;
+                self.expect('=')?;
+                let value = self.id()?;
+                attributes.push((key, value, line));
+                if !self.accept(',') {
+                    self.accept(';');
+                }
+            }
+        }
+        Ok(attributes)
+    }
+
+    /**
+    Constructs the Nodes and links them, once all the positions are known.
+     */
+    fn build(self) -> Result<Vec<Node>, Error> {
+        let mut nodes = Vec::with_capacity(self.nodes.len());
+        let mut anchors = Vec::new();
+        for (id, attributes) in &self.nodes {
+            let mut node = Node::new(id, coordinate!());
+            let mut label = None;
+            let mut placed = false;
+            for (key, value, line) in attributes {
+                let err = |reason: String| Error::ParseError {
+                    line: *line,
+                    reason,
+                };
+                match key.as_str() {
+                    "pos" => {
+                        node.geo = position(value).map_err(err)?;
+                        placed = true;
+                    }
+                    "color" => {
+                        if let Some(color) = color(value).map_err(err)? {
+                            node.color = Some(color);
+                        }
+                    }
+                    "label" => label = Some(value.clone()),
+                    "shape" => node.shape = shape(value),
+                    "pathtracer_hash" => {
+                        // A hash of 0 marks links as disconnected.
+                        node.hash = value
+                            .parse::<u64>()
+                            .ok()
+                            .filter(|h| *h != 0)
+                            .ok_or_else(|| err(format!("invalid pathtracer_hash '{}'", value)))?;
+                    }
+                    _ => (),
+                }
+            }
+            // Nodes written without a name have their hash as the id.
+            node.label =
+                label.or_else(|| Some(id.clone()).filter(|id| *id != node.hash.to_string()));
+            if placed {
+                anchors.push(nodes.len());
+            }
+            nodes.push(node);
+        }
+
+        let graph_style = self
+            .splines
+            .as_deref()
+            .map_or(EdgeStyle::default(), splines);
+        for edge in &self.edges {
+            let mut style = graph_style;
+            let mut weight = None;
+            let (mut paint, mut width, mut arrow) = (None, None, None);
+            let (mut curvature, mut stroke) = (None, Stroke::Solid);
+            for (key, value, line) in &edge.attributes {
+                let err = |reason: String| Error::ParseError {
+                    line: *line,
+                    reason,
+                };
+                match key.as_str() {
+                    "pathtracer_splines" => style = splines(value),
+                    "weight" => weight = Some(number(value).map_err(err)?),
+                    "color" => paint = color(value).map_err(err)?.map(Paint::Solid),
+                    "penwidth" => width = Some(number(value).map_err(err)?),
+                    "arrowhead" => arrow = arrowhead(value),
+                    "pathtracer_curvature" => {
+                        curvature =
+                            Some(value.parse::<i32>().map_err(|e| {
+                                err(format!("invalid curvature '{}': {}", value, e))
+                            })?)
+                    }
+                    "style" if value.contains("dashed") => stroke = Stroke::Dashed,
+                    "style" if value.contains("dotted") => stroke = Stroke::Dotted,
+                    _ => (),
+                }
+            }
+            if let (EdgeStyle::Ellipse, Some(curvature)) = (style, curvature) {
+                style = EdgeStyle::Bezier(curvature);
+            }
+            let to = &nodes[self.index[&edge.to]];
+            let (t, position) = (to.hash, to.position());
+            let from = &mut nodes[self.index[&edge.from]];
+            from.links.push(HL {
+                style,
+                from: Some(from.position()),
+                to: Some(position),
+                weight,
+                paint,
+                width,
+                arrow,
+                stroke,
+                ..HL::new(from.hash, t)
+            });
+        }
+        place(&mut nodes, &anchors);
+        Ok(nodes)
+    }
+}
+
+/**
+Lays out the Nodes which are not anchors, around the anchors which keep their positions.
+ */
+fn place(nodes: &mut [Node], anchors: &[usize]) {
+    if anchors.len() == nodes.len() {
+        return;
+    }
+    let (min, max) = anchors
+        .iter()
+        .map(|&i| nodes[i].geo)
+        .fold(None, |bounds: Option<(Point, Point)>, geo| {
+            Some(bounds.map_or((geo, geo), |(min, max)| {
+                (
+                    Point::new(min.x.min(geo.x), min.y.min(geo.y)),
+                    Point::new(max.x.max(geo.x), max.y.max(geo.y)),
+                )
+            }))
+        })
+        .unwrap_or_default();
+    let margin = 20.0 * (nodes.len() - anchors.len()).max(3) as f64;
+    let bound = |v: f64| v.clamp(f64::from(i32::MIN), f64::from(i32::MAX)) as i32;
+    layout::ForceDirected::new(
+        Coordinate::new(bound(min.x - margin), bound(min.y - margin)),
+        Coordinate::new(bound(max.x + margin), bound(max.y + margin)),
+    )
+    .layout_anchored(nodes, anchors);
+}
+
+/**
+Parses a position in the form "x,y", with an optional "!" as Graphviz uses to pin Nodes.
+ */
+fn position(value: &str) -> Result<Point, String> {
+    let invalid = || format!("invalid pos '{}', expected \"x,y\"", value);
+    let (x, y) = value
+        .trim_end_matches('!')
+        .split_once(',')
+        .ok_or_else(invalid)?;
+    let axis = |v: &str| -> Result<f64, String> {
+        let v = v.trim().parse::<f64>().map_err(|_| invalid())?;
+        if v.is_finite() {
+            Ok(v)
+        } else {
+            Err(format!("pos '{}' is out of range", value))
+        }
+    };
+    Ok(Point::new(axis(x)?, axis(y)?))
+}
+
+/**
+Parses a hex color or one of the basic color names, other names are ignored.
+ */
+fn color(value: &str) -> Result<Option<image::Rgba<u8>>, String> {
+    if value.starts_with('#') {
+        return tools::from_hex(value)
+            .map(Some)
+            .ok_or_else(|| format!("invalid color '{}', expected #rrggbb or #rrggbbaa", value));
+    }
+    let rgba = match value.to_ascii_lowercase().as_str() {
+        "black" => [0, 0, 0, 255],
+        "white" => [255, 255, 255, 255],
+        "red" => [255, 0, 0, 255],
+        "green" => [0, 255, 0, 255],
+        "blue" => [0, 0, 255, 255],
+        "yellow" => [255, 255, 0, 255],
+        "gray" | "grey" => [190, 190, 190, 255],
+        "transparent" => [255, 255, 254, 0],
+        _ => {
+            warn!("unsupported color '{}' is ignored", value);
+            return Ok(None);
+        }
+    };
+    Ok(Some(image::Rgba(rgba)))
+}
+
+fn number(value: &str) -> Result<u32, String> {
+    value
+        .parse::<f64>()
+        .ok()
+        .filter(|v| v.is_finite() && *v >= 0.0 && *v <= f64::from(u32::MAX))
+        .map(|v| v.round() as u32)
+        .ok_or_else(|| format!("invalid weight '{}'", value))
+}
+
+fn shape_name(shape: &Shape) -> Option<&'static str> {
+    match shape {
+        Shape::Circle => Some("circle"),
+        Shape::Square => Some("square"),
+        Shape::Triangle => Some("triangle"),
+        Shape::Diamond => Some("diamond"),
+        Shape::Hexagon => Some("hexagon"),
+        Shape::Star => Some("star"),
+        Shape::Ring => Some("doublecircle"),
+        Shape::Custom(_) => None,
+    }
+}
+
+/**
+Maps node shapes to the closest Shape, shapes without an equivalent use the shape given to the Map.
+ */
+fn shape(value: &str) -> Option<Shape> {
+    match value {
+        "circle" | "ellipse" | "oval" | "point" => Some(Shape::Circle),
+        "square" | "box" | "rect" | "rectangle" => Some(Shape::Square),
+        "triangle" => Some(Shape::Triangle),
+        "diamond" => Some(Shape::Diamond),
+        "hexagon" => Some(Shape::Hexagon),
+        "star" => Some(Shape::Star),
+        "doublecircle" => Some(Shape::Ring),
+        _ => None,
+    }
+}
+
+fn arrowhead_name(arrow: Arrow) -> &'static str {
+    match arrow {
+        Arrow::Filled => "normal",
+        Arrow::Open => "open",
+    }
+}
+
+/**
+Maps arrowhead shapes to the closest Arrow, shapes without an equivalent are not drawn.
+ */
+fn arrowhead(value: &str) -> Option<Arrow> {
+    match value {
+        "normal" | "inv" | "box" | "diamond" => Some(Arrow::Filled),
+        "open" | "vee" | "empty" | "onormal" => Some(Arrow::Open),
+        _ => None,
+    }
+}
+
+fn splines_name(style: EdgeStyle) -> &'static str {
+    match style {
+        EdgeStyle::Direct => "line",
+        EdgeStyle::Ellipse => "curved",
+        EdgeStyle::Straight => "ortho",
+        EdgeStyle::Bezier(_) => "spline",
+        EdgeStyle::Orthogonal => "polyline",
+    }
+}
+
+fn splines(value: &str) -> EdgeStyle {
+    match value {
+        "curved" | "spline" | "true" => EdgeStyle::Ellipse,
+        "ortho" => EdgeStyle::Straight,
+        "polyline" => EdgeStyle::Orthogonal,
+        _ => EdgeStyle::Direct,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let mut nodes = Node::linked_list(Node::from_list(&[(0, 0), (-10, 10), (20, -20)]));
+        nodes[0].color = Some(image::Rgba([1, 2, 3, 255]));
+        nodes[2].label("The \"end\"");
+        nodes[1].shape(Shape::Ring);
+        nodes[1].hl_mut(0).unwrap().style(EdgeStyle::Ellipse);
+        let last = nodes[2].clone();
+        nodes[0].link_weighted(&last, 42);
+        nodes[0].hl_mut(0).unwrap().style(EdgeStyle::Straight);
+        nodes[0].hl_mut(0).unwrap().width(3);
+        nodes[0].hl_mut(0).unwrap().arrow(Arrow::Open);
+        nodes[2].hl_mut(0).unwrap().style(EdgeStyle::Bezier(-40));
+        nodes[2].hl_mut(0).unwrap().stroke(Stroke::Dotted);
+        let first = nodes[0].clone();
+        nodes[2].link(&first);
+        nodes[2].hl_mut(1).unwrap().style(EdgeStyle::Orthogonal);
+        nodes[1]
+            .hl_mut(0)
+            .unwrap()
+            .paint(image::Rgba([255, 0, 0, 255]).into());
+
+        let restored = read(&to_string(&nodes)).unwrap();
+        assert_eq!(restored.len(), nodes.len());
+        for (a, b) in nodes.iter().zip(restored.iter()) {
+            assert_eq!(a.hash, b.hash);
+            assert_eq!(a.geo, b.geo);
+            assert_eq!(a.color, b.color);
+            assert_eq!(a.label, b.label);
+            assert_eq!(
+                a.shape.as_ref().and_then(shape_name),
+                b.shape.as_ref().and_then(shape_name)
+            );
+            assert_eq!(a.links().len(), b.links().len());
+            for (x, y) in a.links().iter().zip(b.links().iter()) {
+                assert_eq!(x.style, y.style);
+                assert_eq!(x.weight, y.weight);
+                assert_eq!(x.paint, y.paint);
+                assert_eq!(x.width, y.width);
+                assert_eq!(x.arrow, y.arrow);
+                assert_eq!(x.stroke, y.stroke);
+                assert_eq!(x.f, y.f);
+                assert_eq!(x.t, y.t);
+                assert_eq!(x.to, y.to);
+            }
+        }
+        let path = Network::new(restored).path("A", "B").unwrap();
+        assert_eq!(path.len(), 2);
+    }
+
+    #[test]
+    fn round_trip_escaped_labels() {
+        let mut nodes = Node::from_list(&[(0, 0), (10, 10), (20, 20)]);
+        nodes[0].label("a\\");
+        nodes[1].label("C:\\dir\\\"quoted\"");
+        nodes[2].label("\\\"\\");
+        let restored = read(&to_string(&nodes)).unwrap();
+        for (a, b) in nodes.iter().zip(restored.iter()) {
+            assert_eq!(a.label, b.label);
+        }
+    }
+
+    #[test]
+    fn zero_id() {
+        let nodes = read("digraph { 0 -> 1 }").unwrap();
+        assert_eq!(nodes.len(), 2);
+        assert_ne!(nodes[0].hash, 0);
+        assert!(nodes[0].hl(0).unwrap().is_connected());
+        assert_eq!(nodes[0].hl(0).unwrap().t, nodes[1].hash);
+        assert!(read("digraph { a [pathtracer_hash=0] }").is_err());
+    }
+
+    #[test]
+    fn graph_splines() {
+        let nodes = read("digraph { splines=ortho; a -> b; b -> c [pathtracer_splines=polyline] }")
+            .unwrap();
+        assert_eq!(nodes[0].hl(0).unwrap().style, EdgeStyle::Straight);
+        assert_eq!(nodes[1].hl(0).unwrap().style, EdgeStyle::Orthogonal);
+        let nodes = read("graph { graph [splines=curved] a -- b }").unwrap();
+        assert_eq!(nodes[0].hl(0).unwrap().style, EdgeStyle::Ellipse);
+
+        // Edges only share the graph's splines when they all have the same style.
+        let mut nodes = Node::linked_list(Node::from_list(&[(0, 0), (10, 0), (20, 0)]));
+        nodes[1].hl_mut(0).unwrap().style(EdgeStyle::Bezier(20));
+        nodes[2].hl_mut(0).unwrap().style(EdgeStyle::Bezier(-20));
+        let content = to_string(&nodes);
+        assert!(content.contains("    splines=spline;"), "{}", content);
+        assert!(content.contains("pathtracer_curvature=-20"), "{}", content);
+        assert!(!content.contains(" curvature"), "{}", content);
+        nodes[1].hl_mut(0).unwrap().style(EdgeStyle::Direct);
+        assert!(!to_string(&nodes).contains("    splines="));
+    }
+
+    #[test]
+    fn network_export() {
+        let nodes = Node::linked_list(Node::from_list(&[(0, 0), (10, 10), (20, 20)]));
+        let network = Network::new(nodes.clone());
+        let content = network.to_dot();
+        assert_eq!(content, network.to_dot());
+        assert_eq!(read(&content).unwrap().len(), 3);
+    }
+
+    #[test]
+    fn undirected_and_subgraphs() {
+        let content = "
+            strict graph G {
+                // A comment.
+                rankdir = LR
+                node [color=red]
+                a -- { b c } [weight=2]
+                subgraph cluster_x { d; /* inner */ e:port; }
+            }";
+        let nodes = read(content).unwrap();
+        assert_eq!(nodes.len(), 5);
+        assert!(nodes
+            .iter()
+            .all(|n| n.color == Some(image::Rgba([255, 0, 0, 255]))));
+        assert_eq!(nodes[0].links().len(), 2);
+        assert_eq!(nodes[1].links().len(), 1);
+        assert_eq!(nodes[1].hl(0).unwrap().t, nodes[0].hash);
+        assert_eq!(nodes[1].hl(0).unwrap().weight, Some(2));
+    }
+
+    #[test]
+    fn layout_without_positions() {
+        let nodes = read("digraph { a -> b -> c -> a }").unwrap();
+        let geos = nodes.iter().map(|n| n.geo).collect::<Vec<_>>();
+        for (i, a) in geos.iter().enumerate() {
+            assert!(geos[i + 1..].iter().all(|b| a != b));
+        }
+        // Links point to where the Nodes were placed.
+        assert_eq!(nodes[0].hl(0).unwrap().to, Some(nodes[1].position()));
+    }
+
+    #[test]
+    fn layout_around_positions() {
+        let nodes =
+            read("digraph { a [pos=\"100,100\"]; b [pos=\"300,100\"]; a -> c -> b }").unwrap();
+        assert_eq!(nodes[0].geo, Point::new(100.0, 100.0));
+        assert_eq!(nodes[1].geo, Point::new(300.0, 100.0));
+        assert_ne!(nodes[2].geo, nodes[0].geo);
+        assert_ne!(nodes[2].geo, nodes[1].geo);
+        assert_eq!(nodes[0].hl(0).unwrap().to, Some(nodes[2].position()));
+    }
+
+    #[test]
+    fn ids_are_names() {
+        let nodes =
+            read("digraph { 1 -> 2 -> 3; x [label=\"Ex\"]; y [pathtracer_hash=7]; 8 [pathtracer_hash=8] }")
+                .unwrap();
+        assert_eq!(nodes[2].hash, Node::new("3", coordinate!()).hash);
+        assert_eq!(nodes[2].label.as_deref(), Some("3"));
+        assert_eq!(nodes[3].label.as_deref(), Some("Ex"));
+        assert_eq!((nodes[4].hash, nodes[4].label.as_deref()), (7, Some("y")));
+        assert_eq!((nodes[5].hash, nodes[5].label.as_ref()), (8, None));
+        let path = Network::new(nodes).path_rev("1", "3").unwrap();
+        assert_eq!(path.len(), 3);
+
+        // Names are written as ids, and kept when read back.
+        let content = to_string(&read("digraph { a -> b; c [label=\"a\"] }").unwrap());
+        assert!(content.contains("\"a\" -> \"b\""), "{}", content);
+        let nodes = read(&content).unwrap();
+        let network = Network::new(nodes);
+        assert!(network.get("a").is_some() && network.get("c").is_some());
+        assert_eq!(network.path_rev("a", "b").unwrap().len(), 2);
+    }
+
+    #[test]
+    fn quoted_ids() {
+        let nodes = read("digraph { \"New \\\"York\\\"\" [pos=\"1.6,-2!\"] }").unwrap();
+        assert_eq!(nodes[0], Node::new("New \"York\"", Coordinate::new(2, -2)));
+    }
+
+    #[test]
+    fn invalid_graphs() {
+        let cases = [
+            ("graph { a -> b }", 1),
+            ("digraph {\n a [pos=\"x\"]\n}", 2),
+            ("digraph {\n\n a -> b [weight=heavy] }", 3),
+            ("digraph { a [color=\"#zz0000\"] }", 1),
+            ("tree { }", 1),
+            ("digraph {\n a", 2),
+            ("digraph { a [pos] }", 1),
+            ("digraph { a } b", 1),
+            ("digraph { /* a }", 1),
+        ];
+        for (content, expected) in cases.iter() {
+            match read(content) {
+                Err(Error::ParseError { line, .. }) => assert_eq!(line, *expected, "{}", content),
+                e => panic!("expected ParseError for '{}', got {:?}", content, e),
+            }
+        }
+    }
+}
@@ -2,16 +2,26 @@
 Converts strings to mapped Nodes and Groups.
 */
 
-use super::{consts, coordinate, tools, Coordinate, Group};
+use super::{coordinate, group, tools, Coordinate, Error, Group};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+pub mod dot;
+pub mod snapshot;
 use std::{
-    collections::hash_map::DefaultHasher,
+    collections::{hash_map::DefaultHasher, HashSet},
     fs::OpenOptions,
     hash::{Hash, Hasher},
-    io::{self, prelude::*},
+    io::prelude::*,
 };
 
 /**
 Holds configurations for converting a content String to a path network.
+
+
+## Seed
+
+Groups and Nodes are placed randomly. Without a seed each conversion places them differently,
+with one the same content is always converted to the same positions.
 */
 pub struct CustomConverter<'a> {
     pub split: char,
@@ -19,12 +29,13 @@ pub struct CustomConverter<'a> {
     pub radius: u32,
     pub lambda_tag: &'a dyn Fn(&str) -> bool,
     pub link_groups: bool,
+    pub seed: Option<u64>,
 }
 
 /**
 Reads from the provided file, and converts to a path network using default settings.
 */
-pub fn convert_file(path: &str, lambda: &dyn Fn(&str) -> bool) -> Result<Vec<Group>, io::Error> {
+pub fn convert_file(path: &str, lambda: &dyn Fn(&str) -> bool) -> Result<Vec<Group>, Error> {
     let content = content(path)?;
     Ok(convert(&content, &lambda))
 }
@@ -32,7 +43,7 @@ pub fn convert_file(path: &str, lambda: &dyn Fn(&str) -> bool) -> Result<Vec<Gro
 /**
 Reads from the provided file, and returns content.
 */
-fn content(path: &str) -> Result<String, io::Error> {
+fn content(path: &str) -> Result<String, Error> {
     let mut file = OpenOptions::new().read(true).open(path)?;
 
     let mut contents = String::new();
@@ -57,22 +68,39 @@ impl<'a> CustomConverter<'a> {
         node_range: u32,
         radius: u32,
         lambda_tag: &'a dyn Fn(&str) -> bool,
-    ) -> CustomConverter {
+    ) -> CustomConverter<'a> {
         CustomConverter {
             split,
             node_range,
             radius,
             lambda_tag,
             link_groups: true,
+            seed: None,
         }
     }
 }
 
 /**
 Constructs a vector of groups and links using a CustomConverter and the string to analyze.
+
+
+## Examples
+
+```
+# use pathtracer::data::{self, CustomConverter};
+let tag = |_: &str| true;
+let mut cct = CustomConverter::new('\n', 30, 120, &tag);
+cct.seed = Some(7);
+let a = data::convert_inner("a\nb\na", &cct).unwrap();
+let b = data::convert_inner("a\nb\na", &cct).unwrap();
+assert_eq!(a[0].nodes[1].geo, b[0].nodes[1].geo);
+```
 */
-pub fn convert_inner(content: &str, cct: &CustomConverter) -> io::Result<Vec<Group>> {
-    let mut gr_bool_arr: [bool; consts::NETWORK_REM] = [false; consts::NETWORK_REM];
+pub fn convert_inner(content: &str, cct: &CustomConverter) -> Result<Vec<Group>, Error> {
+    let mut seen: HashSet<u64> = HashSet::new();
+    let mut rng = cct
+        .seed
+        .map_or_else(StdRng::from_entropy, StdRng::seed_from_u64);
 
     let lines = content
         .split(cct.split)
@@ -81,22 +109,22 @@ pub fn convert_inner(content: &str, cct: &CustomConverter) -> io::Result<Vec<Gro
 
     let lines = lines.iter().fold(vec![], |acc, hash| {
         let hash = calculate_hash(hash);
-        let pos = (hash % consts::NETWORK_REM as u64) as usize;
-        if !gr_bool_arr[pos] {
-            gr_bool_arr[pos] = true;
-            push_group(acc, hash)
+        if seen.insert(hash) {
+            push_group(acc, hash, &mut rng)
         } else {
-            push_node(acc, hash)
+            push_node(acc, hash, &mut rng)
         }
     });
     Ok(lines)
 }
 
-fn push_group(mut groups: Vec<Group>, hash: u64) -> Vec<Group> {
-    let mut group = Group::new("", coordinate::gen_radius(Coordinate::new(1, 0), 0, 100));
+fn push_group<R: Rng>(mut groups: Vec<Group>, hash: u64, rng: &mut R) -> Vec<Group> {
+    let geo = coordinate::gen_radius_with(rng, Coordinate::new(1, 0), 0, 100);
+    let mut group = Group::new("", geo);
     group.settings.hash = hash;
-    group.settings.color = tools::seed_rgb(hash);
-    group.new_node_min_max(groups.len() as u32, 40);
+    group.settings.color = Some(tools::seed_rgb(hash));
+    let min = groups.len() as u32;
+    group::add_node_with(rng, &mut group, None, Some(min), Some(40));
     if !groups.is_empty() {
         group.nodes[0].link(groups.last().unwrap().nodes.last().unwrap());
     }
@@ -104,12 +132,12 @@ fn push_group(mut groups: Vec<Group>, hash: u64) -> Vec<Group> {
     groups
 }
 
-fn push_node(mut groups: Vec<Group>, hash: u64) -> Vec<Group> {
+fn push_node<R: Rng>(mut groups: Vec<Group>, hash: u64, rng: &mut R) -> Vec<Group> {
     let index = groups
         .iter()
         .position(|g| g.settings.hash == hash)
         .expect("Group located, but no hash matching.");
-    groups[index].new_node_min_max(index as u32, 40);
+    group::add_node_with(rng, &mut groups[index], None, Some(index as u32), Some(40));
     groups
 }
 
@@ -145,6 +173,7 @@ mod tests {
             radius: 50,
             lambda_tag: &|_x| true,
             link_groups: true,
+            seed: None,
         };
 
         let content = "a-b-c-a-b-c-b--b-b-c";
@@ -152,6 +181,23 @@ mod tests {
         eval_result(res);
     }
 
+    #[test]
+    fn test_convert_seeded() {
+        let render = |seed| {
+            let mut cct = CustomConverter::new('-', 10, 50, &|_x| true);
+            cct.seed = Some(seed);
+            let groups = convert_inner("a-b-c-a-b-c-b--b-b-c", &cct).unwrap();
+            eval_result(groups.clone());
+            super::super::Map::new()
+                .map(&groups)
+                .consume()
+                .image()
+                .clone()
+        };
+        assert_eq!(render(3).into_raw(), render(3).into_raw());
+        assert_ne!(render(3).into_raw(), render(4).into_raw());
+    }
+
     #[test]
     fn test_convert() {
         let content = "a\nb\nc\na\nb\nc\nb\nb\nb\nc";
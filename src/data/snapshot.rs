@@ -0,0 +1,617 @@
+/*!
+Saves and restores Nodes and Groups, including their links.
+
+The format is line based, each line is a record with a kind followed by key=value pairs.
+
+```text
+# pathtracer snapshot 1
+node hash=7 geo=10,20 color=#ff0000 radius=4 shape=star label="Home base"
+link f=7 t=9 style=bezier:25 from=10,20 to=30,40 weight=5 paint=#ff0000:#0000ff width=3 arrow=filled
+group hash=9 geo=30,40 color=#00ff00
+node hash=11 geo=-5,5
+end
+```
+
+Custom shapes are not written, as they can not be restored.
+Labels are quoted, with quotes and backslashes in them escaped by a backslash, and line breaks written as `\n` and `\r`.
+Links belong to the node or group record before them.
+Nodes between a group record and end are the members of the group, with positions relative to the group.
+ */
+
+use super::super::{map::theme::Paint, *};
+use std::{fs::File, io::prelude::*};
+
+const HEADER: &str = "# pathtracer snapshot 1";
+
+/**
+Nodes and Groups restored from a snapshot.
+ */
+#[derive(Clone, Debug, Default)]
+pub struct Snapshot {
+    pub nodes: Vec<Node>,
+    pub groups: Vec<Group>,
+}
+
+/**
+Where the records being read are added.
+ */
+enum Target {
+    Nodes,
+    Group,
+    Member,
+}
+
+/**
+Writes the Nodes and Groups to a file.
+
+
+## Examples
+
+```
+# use pathtracer::{data::snapshot, *};
+# fn main() -> Result<(), Error> {
+let nodes = Node::linked_list(Node::from_list(&[(0, 0), (10, 10)]));
+snapshot::save("/tmp/example_snapshot.txt", &nodes, &[])?;
+let restored = snapshot::load("/tmp/example_snapshot.txt")?;
+assert_eq!(restored.nodes, nodes);
+assert_eq!(restored.nodes[1].links(), nodes[1].links());
+# Ok(())
+# }
+```
+
+
+## Errors
+
+Could not create or write to the file.
+ */
+pub fn save(path: &str, nodes: &[Node], groups: &[Group]) -> Result<(), Error> {
+    let mut file = File::create(path)?;
+    write(&mut file, nodes, groups)
+}
+
+/**
+Reads Nodes and Groups from a file.
+
+
+## Errors
+
+Could not open or read the file.
+
+Error::ParseError if a record is invalid.
+ */
+pub fn load(path: &str) -> Result<Snapshot, Error> {
+    let mut contents = String::new();
+    File::open(path)?.read_to_string(&mut contents)?;
+    read(&contents)
+}
+
+/**
+Writes the Nodes and Groups to any writer.
+
+
+## Errors
+
+Could not write.
+ */
+pub fn write<W: Write>(writer: &mut W, nodes: &[Node], groups: &[Group]) -> Result<(), Error> {
+    writeln!(writer, "{}", HEADER)?;
+    for node in nodes {
+        write_node(writer, "node", node)?;
+    }
+    for group in groups {
+        write_node(writer, "group", &group.settings)?;
+        for node in &group.nodes {
+            write_node(writer, "node", node)?;
+        }
+        writeln!(writer, "end")?;
+    }
+    Ok(())
+}
+
+/**
+Returns the snapshot as a String.
+ */
+pub fn to_string(nodes: &[Node], groups: &[Group]) -> String {
+    let mut buffer = Vec::new();
+    // Writing to a Vec can not fail.
+    let _ = write(&mut buffer, nodes, groups);
+    String::from_utf8_lossy(&buffer).into_owned()
+}
+
+fn write_node<W: Write>(writer: &mut W, kind: &str, node: &Node) -> Result<(), Error> {
+    write!(
+        writer,
+        "{} hash={} geo={},{}",
+        kind, node.hash, node.geo.x, node.geo.y
+    )?;
+    if let Some(color) = node.color {
+        write!(writer, " color={}", tools::to_hex(color))?;
+    }
+    if let Some(radius) = node.radius {
+        write!(writer, " radius={}", radius)?;
+    }
+    if let Some(name) = node.shape.as_ref().and_then(shape_name) {
+        write!(writer, " shape={}", name)?;
+    }
+    if let Some(label) = &node.label {
+        write!(writer, " label={}", quote(label))?;
+    }
+    writeln!(writer)?;
+
+    for link in node.links() {
+        write!(
+            writer,
+            "link f={} t={} style={}",
+            link.f,
+            link.t,
+            style_name(link.style)
+        )?;
+        if let Some(from) = link.from {
+            write!(writer, " from={},{}", from.x, from.y)?;
+        }
+        if let Some(to) = link.to {
+            write!(writer, " to={},{}", to.x, to.y)?;
+        }
+        if let Some(weight) = link.weight {
+            write!(writer, " weight={}", weight)?;
+        }
+        match link.paint {
+            Some(Paint::Solid(color)) => write!(writer, " paint={}", tools::to_hex(color))?,
+            Some(Paint::Gradient(from, to)) => write!(
+                writer,
+                " paint={}:{}",
+                tools::to_hex(from),
+                tools::to_hex(to)
+            )?,
+            None => (),
+        }
+        if let Some(width) = link.width {
+            write!(writer, " width={}", width)?;
+        }
+        if let Some(arrow) = link.arrow {
+            write!(writer, " arrow={}", arrow_name(arrow))?;
+        }
+        match link.stroke {
+            Stroke::Solid => (),
+            Stroke::Dashed => write!(writer, " stroke=dashed")?,
+            Stroke::Dotted => write!(writer, " stroke=dotted")?,
+        }
+        writeln!(writer)?;
+    }
+    Ok(())
+}
+
+/**
+Parses a snapshot.
+
+
+## Examples
+
+```
+# use pathtracer::{data::snapshot, *};
+let mut group = Group::new("A", Coordinate::new(50, 50));
+group.radius(20);
+group.add(10);
+let content = snapshot::to_string(&[], &[group.clone()]);
+let restored = snapshot::read(&content).unwrap();
+assert_eq!(restored.groups[0], group);
+assert_eq!(restored.groups[0].nodes().len(), 10);
+```
+
+
+## Errors
+
+Error::ParseError if a record is invalid.
+ */
+pub fn read(content: &str) -> Result<Snapshot, Error> {
+    let mut snapshot = Snapshot::default();
+    let mut target = Target::Nodes;
+
+    for (i, line) in content.lines().enumerate() {
+        let err = |reason: String| Error::ParseError {
+            line: i + 1,
+            reason,
+        };
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let mut tokens = split(line).map_err(err)?.into_iter();
+        let kind = tokens.next().unwrap_or_default();
+        let pairs = tokens
+            .map(|t| {
+                t.split_once('=')
+                    .ok_or_else(|| format!("expected key=value, found '{}'", t))
+            })
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(err)?;
+
+        match kind {
+            "node" => {
+                let node = read_node(&pairs).map_err(err)?;
+                match target {
+                    Target::Nodes => snapshot.nodes.push(node),
+                    Target::Group | Target::Member => {
+                        // Members are stored relative to the group already.
+                        snapshot.groups.last_mut().unwrap().nodes.push(node);
+                        target = Target::Member;
+                    }
+                }
+            }
+            "group" => {
+                if !matches!(target, Target::Nodes) {
+                    return Err(err(
+                        "group started before the previous group ended".to_string()
+                    ));
+                }
+                let node = read_node(&pairs).map_err(err)?;
+                snapshot.groups.push(Group::from(node));
+                target = Target::Group;
+            }
+            "link" => {
+                let link = read_link(&pairs).map_err(err)?;
+                let node = match target {
+                    Target::Nodes => snapshot.nodes.last_mut(),
+                    Target::Group => snapshot.groups.last_mut().map(|g| g.set()),
+                    Target::Member => snapshot.groups.last_mut().and_then(|g| g.nodes.last_mut()),
+                };
+                node.ok_or_else(|| err("link without a node to belong to".to_string()))?
+                    .links
+                    .push(link);
+            }
+            "end" => {
+                if matches!(target, Target::Nodes) {
+                    return Err(err("end without a group".to_string()));
+                }
+                target = Target::Nodes;
+            }
+            _ => return Err(err(format!("unknown record '{}'", kind))),
+        }
+    }
+
+    if !matches!(target, Target::Nodes) {
+        return Err(Error::ParseError {
+            line: content.lines().count(),
+            reason: "group is missing end".to_string(),
+        });
+    }
+    Ok(snapshot)
+}
+
+fn read_node(pairs: &[(&str, &str)]) -> Result<Node, String> {
+    let mut node = Node::new("", coordinate!());
+    let mut hash = None;
+    for &(key, value) in pairs {
+        match key {
+            "hash" => hash = Some(number(key, value)?),
+            "geo" => node.geo = point(key, value)?,
+            "color" => {
+                node.color = tools::from_hex(value).map(Some).ok_or_else(|| {
+                    format!("invalid color '{}', expected #rrggbb or #rrggbbaa", value)
+                })?
+            }
+            "radius" => node.radius = Some(number(key, value)?),
+            "shape" => node.shape = Some(shape(value)?),
+            "label" => node.label = Some(unquote(value)?),
+            _ => return Err(format!("unknown node key '{}'", key)),
+        }
+    }
+    node.hash = hash.ok_or("node is missing a hash")?;
+    Ok(node)
+}
+
+fn read_link(pairs: &[(&str, &str)]) -> Result<HL, String> {
+    let mut link = HL::new(0, 0);
+    for &(key, value) in pairs {
+        match key {
+            "f" => link.f = number(key, value)?,
+            "t" => link.t = number(key, value)?,
+            "style" => link.style = style(value)?,
+            "from" => link.from = Some(coordinate(key, value)?),
+            "to" => link.to = Some(coordinate(key, value)?),
+            "weight" => link.weight = Some(number(key, value)?),
+            "paint" => link.paint = Some(paint(value)?),
+            "width" => link.width = Some(number(key, value)?),
+            "arrow" => link.arrow = Some(arrow(value)?),
+            "stroke" => link.stroke = stroke(value)?,
+            _ => return Err(format!("unknown link key '{}'", key)),
+        }
+    }
+    Ok(link)
+}
+
+/**
+Splits the line on whitespace, except inside quotes.
+ */
+fn split(line: &str) -> Result<Vec<&str>, String> {
+    let mut tokens = Vec::new();
+    let (mut start, mut quoted, mut escaped) = (None, false, false);
+    for (i, c) in line.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if quoted => escaped = true,
+            '"' => quoted = !quoted,
+            c if c.is_whitespace() && !quoted => {
+                if let Some(start) = start.take() {
+                    tokens.push(&line[start..i]);
+                }
+                continue;
+            }
+            _ => (),
+        }
+        start.get_or_insert(i);
+    }
+    if quoted {
+        return Err("unterminated quote".to_string());
+    }
+    if let Some(start) = start {
+        tokens.push(&line[start..]);
+    }
+    Ok(tokens)
+}
+
+fn quote(value: &str) -> String {
+    let mut result = String::from('"');
+    for c in value.chars() {
+        match c {
+            '\\' => result.push_str("\\\\"),
+            '"' => result.push_str("\\\""),
+            '\n' => result.push_str("\\n"),
+            '\r' => result.push_str("\\r"),
+            c => result.push(c),
+        }
+    }
+    result.push('"');
+    result
+}
+
+fn unquote(value: &str) -> Result<String, String> {
+    let inner = value
+        .strip_prefix('"')
+        .and_then(|v| v.strip_suffix('"'))
+        .ok_or_else(|| format!("expected a quoted value, found '{}'", value))?;
+    let mut result = String::new();
+    let mut chars = inner.chars();
+    while let Some(c) = chars.next() {
+        result.push(if c == '\\' {
+            match chars.next() {
+                Some('n') => '\n',
+                Some('r') => '\r',
+                next => next.unwrap_or(c),
+            }
+        } else {
+            c
+        });
+    }
+    Ok(result)
+}
+
+fn number<T: std::str::FromStr>(key: &str, value: &str) -> Result<T, String> {
+    value
+        .parse::<T>()
+        .map_err(|_| format!("invalid {} '{}'", key, value))
+}
+
+fn paint(value: &str) -> Result<Paint, String> {
+    let color = |hex: &str| {
+        tools::from_hex(hex).ok_or_else(|| {
+            format!(
+                "invalid paint '{}', expected #rrggbb or #rrggbb:#rrggbb",
+                value
+            )
+        })
+    };
+    match value.split_once(':') {
+        Some((from, to)) => Ok(Paint::Gradient(color(from)?, color(to)?)),
+        None => Ok(Paint::Solid(color(value)?)),
+    }
+}
+
+fn coordinate(key: &str, value: &str) -> Result<Coordinate, String> {
+    let (x, y) = value
+        .split_once(',')
+        .ok_or_else(|| format!("invalid {} '{}', expected x,y", key, value))?;
+    Ok(Coordinate::new(number(key, x)?, number(key, y)?))
+}
+
+fn point(key: &str, value: &str) -> Result<Point, String> {
+    let (x, y) = value
+        .split_once(',')
+        .ok_or_else(|| format!("invalid {} '{}', expected x,y", key, value))?;
+    Ok(Point::new(number(key, x)?, number(key, y)?))
+}
+
+fn style_name(style: EdgeStyle) -> String {
+    match style {
+        EdgeStyle::Direct => "direct".to_string(),
+        EdgeStyle::Ellipse => "ellipse".to_string(),
+        EdgeStyle::Straight => "straight".to_string(),
+        EdgeStyle::Bezier(curvature) => format!("bezier:{}", curvature),
+        EdgeStyle::Orthogonal => "orthogonal".to_string(),
+    }
+}
+
+fn style(value: &str) -> Result<EdgeStyle, String> {
+    match value {
+        "direct" => Ok(EdgeStyle::Direct),
+        "ellipse" => Ok(EdgeStyle::Ellipse),
+        "straight" => Ok(EdgeStyle::Straight),
+        "orthogonal" => Ok(EdgeStyle::Orthogonal),
+        _ => match value.strip_prefix("bezier:") {
+            Some(curvature) => Ok(EdgeStyle::Bezier(number("curvature", curvature)?)),
+            None => Err(format!("unknown style '{}'", value)),
+        },
+    }
+}
+
+fn shape_name(shape: &Shape) -> Option<&'static str> {
+    match shape {
+        Shape::Circle => Some("circle"),
+        Shape::Square => Some("square"),
+        Shape::Triangle => Some("triangle"),
+        Shape::Diamond => Some("diamond"),
+        Shape::Hexagon => Some("hexagon"),
+        Shape::Star => Some("star"),
+        Shape::Ring => Some("ring"),
+        Shape::Custom(_) => None,
+    }
+}
+
+fn shape(value: &str) -> Result<Shape, String> {
+    match value {
+        "circle" => Ok(Shape::Circle),
+        "square" => Ok(Shape::Square),
+        "triangle" => Ok(Shape::Triangle),
+        "diamond" => Ok(Shape::Diamond),
+        "hexagon" => Ok(Shape::Hexagon),
+        "star" => Ok(Shape::Star),
+        "ring" => Ok(Shape::Ring),
+        _ => Err(format!("unknown shape '{}'", value)),
+    }
+}
+
+fn stroke(value: &str) -> Result<Stroke, String> {
+    match value {
+        "solid" => Ok(Stroke::Solid),
+        "dashed" => Ok(Stroke::Dashed),
+        "dotted" => Ok(Stroke::Dotted),
+        _ => Err(format!("unknown stroke '{}'", value)),
+    }
+}
+
+fn arrow_name(arrow: Arrow) -> &'static str {
+    match arrow {
+        Arrow::Filled => "filled",
+        Arrow::Open => "open",
+    }
+}
+
+fn arrow(value: &str) -> Result<Arrow, String> {
+    match value {
+        "filled" => Ok(Arrow::Filled),
+        "open" => Ok(Arrow::Open),
+        _ => Err(format!("unknown arrow '{}'", value)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_same_node(a: &Node, b: &Node) {
+        assert_eq!(a.hash, b.hash);
+        assert_eq!(a.geo, b.geo);
+        assert_eq!(a.color, b.color);
+        assert_eq!(a.radius, b.radius);
+        assert_eq!(a.label, b.label);
+        assert_eq!(
+            a.shape.as_ref().and_then(shape_name),
+            b.shape.as_ref().and_then(shape_name)
+        );
+        assert_eq!(a.links(), b.links());
+    }
+
+    #[test]
+    fn round_trip_nodes() {
+        let mut nodes = Node::linked_list(Node::from_list(&[(0, 0), (-10, 10), (20, -20)]));
+        nodes[0].color = Some(image::Rgba([1, 2, 3, 255]));
+        nodes[1].radius = Some(9);
+        nodes[1].shape(Shape::Hexagon);
+        nodes[1].label("Say \"hi\" \\ wave");
+        nodes[2].geo = Point::new(20.25, -19.5);
+        nodes[1].hl_mut(0).unwrap().style(EdgeStyle::Ellipse);
+        let last = nodes[2].clone();
+        nodes[0].link_weighted(&last, 42);
+        nodes[0].hl_mut(0).unwrap().style(EdgeStyle::Straight);
+        nodes[0].hl_mut(0).unwrap().width(4);
+        nodes[0].hl_mut(0).unwrap().arrow(Arrow::Open);
+        nodes[1].hl_mut(0).unwrap().arrow(Arrow::Filled);
+        nodes[1].hl_mut(0).unwrap().stroke(Stroke::Dashed);
+        nodes[2].hl_mut(0).unwrap().style(EdgeStyle::Bezier(-15));
+        let (red, blue) = (image::Rgba([255, 0, 0, 255]), image::Rgba([0, 0, 255, 128]));
+        nodes[2]
+            .hl_mut(0)
+            .unwrap()
+            .paint(Paint::Gradient(red, blue));
+        nodes[1].hl_mut(0).unwrap().paint(red.into());
+
+        let restored = read(&to_string(&nodes, &[])).unwrap();
+        assert_eq!(restored.nodes.len(), nodes.len());
+        for (a, b) in nodes.iter().zip(restored.nodes.iter()) {
+            assert_same_node(a, b);
+        }
+    }
+
+    #[test]
+    fn round_trip_multiline_labels() {
+        let mut nodes = Node::from_list(&[(0, 0), (10, 10)]);
+        nodes[0].label("line1\nline2");
+        nodes[1].label("a\r\n\\n\"b\\");
+        let content = to_string(&nodes, &[]);
+        assert!(content.contains("label=\"line1\\nline2\""));
+        let restored = read(&content).unwrap();
+        for (a, b) in nodes.iter().zip(restored.nodes.iter()) {
+            assert_same_node(a, b);
+        }
+    }
+
+    #[test]
+    fn round_trip_groups() {
+        let mut a = Group::new("A", Coordinate::new(100, 100));
+        a.radius(30);
+        a.color(image::Rgba([200, 10, 10, 255]));
+        a.label("Cluster A");
+        a.add(20);
+        a.nodes = Node::linked_list(a.nodes);
+        let mut b = Group::new("B", Coordinate::new(-50, 0));
+        b.add(5);
+        a.link(&b);
+
+        let nodes = Node::from_list(&[(1, 1)]);
+        let restored = read(&to_string(&nodes, &[a.clone(), b.clone()])).unwrap();
+        assert_eq!(restored.nodes.len(), 1);
+        assert_eq!(restored.groups.len(), 2);
+        for (x, y) in [a, b].iter().zip(restored.groups.iter()) {
+            assert_same_node(&x.settings, &y.settings);
+            assert_eq!(x.nodes.len(), y.nodes.len());
+            for (n, m) in x.nodes.iter().zip(y.nodes.iter()) {
+                assert_same_node(n, m);
+            }
+        }
+    }
+
+    #[test]
+    fn empty_group() {
+        let group = Group::new("A", Coordinate::new(1, 2));
+        let restored = read(&to_string(&[], &[group])).unwrap();
+        assert!(restored.groups[0].nodes.is_empty());
+    }
+
+    #[test]
+    fn invalid_records() {
+        let cases = [
+            ("node geo=0,0", 1),
+            ("node hash=1 geo=0;0", 1),
+            ("node hash=1\nlink f=1 t=2 style=wavy", 2),
+            ("node hash=1\nlink f=1 t=2 arrow=diamond", 2),
+            ("node hash=1\nlink f=1 t=2 style=bezier:x", 2),
+            ("node hash=1\nlink f=1 t=2 stroke=wavy", 2),
+            ("link f=1 t=2", 1),
+            ("node hash=1\nlink f=1 t=2 paint=red", 2),
+            ("node hash=1\nlink f=1 t=2 paint=#ff0000:", 2),
+            ("node hash=1 shape=blob", 1),
+            ("node hash=1 label=\"open", 1),
+            ("node hash=1 label=bare", 1),
+            ("group hash=1\nnode hash=2", 2),
+            ("end", 1),
+            ("edge f=1", 1),
+        ];
+        for (content, expected) in cases.iter() {
+            match read(content) {
+                Err(Error::ParseError { line, .. }) => assert_eq!(line, *expected, "{}", content),
+                e => panic!("expected ParseError for '{}', got {:?}", content, e),
+            }
+        }
+    }
+}
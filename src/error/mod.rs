@@ -0,0 +1,150 @@
+/*!
+Errors returned throughout the crate.
+ */
+
+use std::{fmt, io};
+
+/**
+Error for graph, file and image operations.
+
+
+## NodeNotFound
+
+The named Node does not exist in the Network.
+
+
+## NoPath
+
+The Nodes exist, but there is no path connecting them.
+
+
+## LinkIndexOutOfRange
+
+The requested HL index is larger than the number of links the Node has.
+
+
+## ParseError
+
+A line could not be interpreted, line numbers start at 1.
+
+
+## InvalidArgument
+
+A value given to a function is outside of the range it accepts.
+
+
+## NotMapped
+
+The Map has no image or SVG document, since nothing has been mapped on it.
+
+
+## ImageError
+
+The image could not be encoded or saved.
+
+
+## GifError
+
+The gif could not be encoded or written.
+
+
+## Io
+
+Reading or writing a file failed.
+ */
+#[derive(Debug)]
+pub enum Error {
+    NodeNotFound(String),
+    NoPath,
+    LinkIndexOutOfRange { index: usize, len: usize },
+    ParseError { line: usize, reason: String },
+    InvalidArgument(String),
+    NotMapped,
+    ImageError(image::ImageError),
+    GifError(gif::EncodingError),
+    Io(io::Error),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::NodeNotFound(name) => write!(f, "node '{}' does not exist in the network", name),
+            Error::NoPath => write!(f, "not a valid path"),
+            Error::LinkIndexOutOfRange { index, len } => write!(
+                f,
+                "link index {} out of range, the node has {} links",
+                index, len
+            ),
+            Error::ParseError { line, reason } => write!(f, "line {}: {}", line, reason),
+            Error::InvalidArgument(reason) => write!(f, "invalid argument: {}", reason),
+            Error::NotMapped => write!(f, "nothing has been mapped"),
+            Error::ImageError(e) => write!(f, "image error: {}", e),
+            Error::GifError(e) => write!(f, "gif error: {}", e),
+            Error::Io(e) => write!(f, "io error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::ImageError(e) => Some(e),
+            Error::GifError(e) => Some(e),
+            Error::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Self {
+        Error::Io(e)
+    }
+}
+
+impl From<image::ImageError> for Error {
+    fn from(e: image::ImageError) -> Self {
+        Error::ImageError(e)
+    }
+}
+
+impl From<gif::EncodingError> for Error {
+    fn from(e: gif::EncodingError) -> Self {
+        Error::GifError(e)
+    }
+}
+
+/**
+Allows the crate's errors to be propagated in functions returning std::io::Result.
+ */
+impl From<Error> for io::Error {
+    fn from(e: Error) -> Self {
+        match e {
+            Error::Io(e) => e,
+            e => io::Error::other(e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn display() {
+        let e = Error::ParseError {
+            line: 3,
+            reason: "expected x,y".to_string(),
+        };
+        assert_eq!(e.to_string(), "line 3: expected x,y");
+    }
+
+    #[test]
+    fn into_io_error() {
+        let e: io::Error = Error::NoPath.into();
+        assert_eq!(e.kind(), io::ErrorKind::Other);
+
+        let e: io::Error = Error::Io(io::Error::from(io::ErrorKind::NotFound)).into();
+        assert_eq!(e.kind(), io::ErrorKind::NotFound);
+    }
+}
@@ -7,6 +7,7 @@ Here they also can be used for pre precise control.
  */
 
 use super::*;
+use rand::Rng;
 use std::cmp;
 
 /**
@@ -28,7 +29,7 @@ pub fn count(list: &[Group]) -> usize {
     list.iter().fold(0, |acc, x| acc + x.nodes.len())
 }
 
-impl<'a> PartialEq for Group {
+impl PartialEq for Group {
     fn eq(&self, other: &Group) -> bool {
         self.hash() == other.hash()
     }
@@ -51,6 +52,32 @@ assert!(group.nodes[0].position().lt(10));
 ```
 */
 pub fn add_node(group: &mut Group, name: Option<&str>, min: Option<u32>, max: Option<u32>) {
+    add_node_with(&mut rand::thread_rng(), group, name, min, max);
+}
+
+/**
+Adds a node to a given group, placed by the given random number generator.
+
+
+## Examples
+
+```
+# use pathtracer::*;
+use rand::{rngs::StdRng, SeedableRng};
+let mut a = Group::new("", Coordinate::new(0, 0));
+let mut b = a.clone();
+group::add_node_with(&mut StdRng::seed_from_u64(1), &mut a, None, Some(5), Some(90));
+group::add_node_with(&mut StdRng::seed_from_u64(1), &mut b, None, Some(5), Some(90));
+assert_eq!(a.nodes[0].geo, b.nodes[0].geo);
+```
+*/
+pub fn add_node_with<R: Rng + ?Sized>(
+    rng: &mut R,
+    group: &mut Group,
+    name: Option<&str>,
+    min: Option<u32>,
+    max: Option<u32>,
+) {
     let name = name.unwrap_or("");
     let min = min.unwrap_or(0);
     let max = max.unwrap_or_else(|| group.dynamic_radius());
@@ -58,9 +85,9 @@ pub fn add_node(group: &mut Group, name: Option<&str>, min: Option<u32>, max: Op
     let mi = cmp::min(min, max);
     let ma = cmp::max(min, max);
 
-    let geo = coordinate::gen_radius(group.settings.geo, mi, ma);
+    let geo = coordinate::gen_radius_point_with(rng, group.settings.geo, mi, ma);
     let mut node = Node::new(name, geo);
-    node.color = group.gen_color(geo);
+    node.color = group.gen_color(geo.position());
     node.radius = group.settings.radius;
     group.push(node);
 }
@@ -89,4 +116,18 @@ mod tests {
         add_node(&mut group, Some("name"), Some(50), Some(20));
         assert_eq!(group.nodes.len(), 2);
     }
+
+    #[test]
+    fn test_add_node_off_grid() {
+        use rand::{rngs::StdRng, SeedableRng};
+        let mut group = Group::new("", Point::new(0.5, 0.5));
+        let mut rng = StdRng::seed_from_u64(7);
+        for _ in 0..10 {
+            add_node_with(&mut rng, &mut group, None, Some(10), Some(11));
+        }
+        for node in &group.nodes {
+            let r = node.geo.x.hypot(node.geo.y);
+            assert!((10.0..11.0).contains(&r), "{:?}", node.geo);
+        }
+    }
 }
@@ -0,0 +1,374 @@
+/*!
+Automatic placement of linked Nodes and Groups.
+
+Positions are computed using a force-directed algorithm, based on Fruchterman and Reingold's
+Graph Drawing by Force-directed Placement. All elements push each other away, while links pull
+the elements they connect together, leaving linked elements close and unrelated ones apart.
+
+```
+# use pathtracer::{layout::ForceDirected, *};
+let mut nodes = Node::linked_list(Node::from_list(&[(0, 0), (0, 0), (0, 0), (0, 0)]));
+ForceDirected::new(coordinate!(), coordinate!(200, 200)).layout(&mut nodes);
+assert!(nodes.iter().all(|n| n.geo.x >= 0.0 && n.geo.x <= 200.0));
+assert_ne!(nodes[0].geo, nodes[1].geo);
+```
+ */
+
+use super::*;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+/**
+Configures a force-directed layout.
+
+
+## Seed
+
+The initial positions are random, generated from the seed.
+The same configuration and links always result in the same layout.
+
+
+## Bounds
+
+Elements are placed with their positions between min and max, inclusive.
+Each axis is bounded by the smaller and larger of the two, so swapped bounds cover the same area.
+ */
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ForceDirected {
+    pub iterations: u32,
+    pub seed: u64,
+    pub min: Coordinate,
+    pub max: Coordinate,
+}
+
+impl Default for ForceDirected {
+    fn default() -> Self {
+        ForceDirected::new(coordinate!(), coordinate!(500, 500))
+    }
+}
+
+impl ForceDirected {
+    /**
+    Constructs a layout within the bounds, using 100 iterations and a seed of 0.
+     */
+    pub fn new(min: Coordinate, max: Coordinate) -> Self {
+        ForceDirected {
+            iterations: 100,
+            seed: 0,
+            min,
+            max,
+        }
+    }
+
+    /**
+    Positions the Nodes and updates their links to match.
+
+    Every Node is moved, their current positions are ignored.
+    Use layout_anchored to keep some of them in place.
+
+    Links to Nodes outside the list do not affect the layout.
+     */
+    pub fn layout(&self, nodes: &mut [Node]) {
+        self.layout_anchored(nodes, &[]);
+    }
+
+    /**
+    Positions the Nodes, except for the ones at the anchor indexes, and updates their links to match.
+
+    Anchors keep their position, even outside the bounds, and only pull and push the other Nodes.
+    Indexes outside the list are ignored.
+
+
+    ## Examples
+
+    ```
+    # use pathtracer::{layout::ForceDirected, *};
+    let mut nodes = Node::linked_list(Node::from_list(&[(0, 0), (0, 0), (-50, 600)]));
+    ForceDirected::default().layout_anchored(&mut nodes, &[2]);
+    assert_eq!(nodes[2].geo, Point::new(-50.0, 600.0));
+    assert_ne!(nodes[0].geo, nodes[1].geo);
+    ```
+     */
+    pub fn layout_anchored(&self, nodes: &mut [Node], anchors: &[usize]) {
+        let fixed = (0..nodes.len())
+            .map(|i| anchors.contains(&i).then(|| nodes[i].geo))
+            .collect::<Vec<_>>();
+        let positions = self.positions(&fixed, &edges(nodes));
+        for (node, pos) in nodes.iter_mut().zip(positions) {
+            node.geo = pos;
+        }
+        relink(nodes);
+    }
+
+    /**
+    Positions the Groups, moving their Nodes along with them, and updates their links to match.
+
+
+    ## Examples
+
+    ```
+    # use pathtracer::{layout::ForceDirected, *};
+    let mut groups = Group::from_list(&[(0, 0), (0, 0), (0, 0)]);
+    let b = groups[1].clone();
+    groups[0].link(&b);
+    for group in groups.iter_mut() {
+        group.add(10);
+    }
+    let before = groups[0].nodes[0].geo;
+    ForceDirected::default().layout_groups(&mut groups);
+    assert_eq!(groups[0].nodes[0].geo, before);
+    assert_ne!(groups[0].position(), groups[1].position());
+    ```
+     */
+    pub fn layout_groups(&self, groups: &mut [Group]) {
+        let mut settings = groups
+            .iter()
+            .map(|g| g.settings.clone())
+            .collect::<Vec<_>>();
+        self.layout(&mut settings);
+        for (group, settings) in groups.iter_mut().zip(settings) {
+            group.settings = settings;
+        }
+    }
+
+    /**
+    Computes a position for each element, given the links between them as index pairs.
+    Elements with a fixed position keep it.
+     */
+    fn positions(&self, fixed: &[Option<Point>], edges: &[(usize, usize)]) -> Vec<Point> {
+        let count = fixed.len();
+        let mut rng = StdRng::seed_from_u64(self.seed);
+        let (a, b) = (self.min, self.max);
+        let (min, max) = (
+            (f64::from(a.x.min(b.x)), f64::from(a.y.min(b.y))),
+            (f64::from(a.x.max(b.x)), f64::from(a.y.max(b.y))),
+        );
+        let (width, height) = ((max.0 - min.0).max(1.0), (max.1 - min.1).max(1.0));
+
+        let mut pos = fixed
+            .iter()
+            .map(|fixed| {
+                let random = (
+                    min.0 + rng.gen::<f64>() * width,
+                    min.1 + rng.gen::<f64>() * height,
+                );
+                fixed.map_or(random, |p| (p.x, p.y))
+            })
+            .collect::<Vec<_>>();
+
+        // The ideal distance between elements.
+        let k = (width * height / count.max(1) as f64).sqrt();
+        let start = width.max(height) / 10.0;
+
+        for i in 0..self.iterations {
+            let mut disp = vec![(0.0, 0.0); count];
+
+            for a in 0..count {
+                for b in a + 1..count {
+                    let (dx, dy, dist) = delta(pos[a], pos[b], &mut rng);
+                    let force = k * k / dist;
+                    disp[a].0 += dx / dist * force;
+                    disp[a].1 += dy / dist * force;
+                    disp[b].0 -= dx / dist * force;
+                    disp[b].1 -= dy / dist * force;
+                }
+            }
+
+            for &(a, b) in edges.iter().filter(|(a, b)| a != b) {
+                let (dx, dy, dist) = delta(pos[a], pos[b], &mut rng);
+                let force = dist * dist / k;
+                disp[a].0 -= dx / dist * force;
+                disp[a].1 -= dy / dist * force;
+                disp[b].0 += dx / dist * force;
+                disp[b].1 += dy / dist * force;
+            }
+
+            // The temperature limits how far an element moves, and cools down linearly.
+            let t = start * (1.0 - f64::from(i) / f64::from(self.iterations));
+            for ((p, d), _) in pos
+                .iter_mut()
+                .zip(disp)
+                .zip(fixed)
+                .filter(|(_, fixed)| fixed.is_none())
+            {
+                let length = (d.0 * d.0 + d.1 * d.1).sqrt();
+                if length > 0.0 {
+                    p.0 += d.0 / length * length.min(t);
+                    p.1 += d.1 / length * length.min(t);
+                }
+                p.0 = p.0.clamp(min.0, max.0);
+                p.1 = p.1.clamp(min.1, max.1);
+            }
+        }
+
+        pos.into_iter().map(|(x, y)| Point::new(x, y)).collect()
+    }
+}
+
+/**
+Returns the links between the Nodes as index pairs.
+ */
+fn edges(nodes: &[Node]) -> Vec<(usize, usize)> {
+    let index = nodes
+        .iter()
+        .enumerate()
+        .map(|(i, n)| (n.hash, i))
+        .collect::<HashMap<_, _>>();
+    nodes
+        .iter()
+        .enumerate()
+        .flat_map(|(i, n)| n.links.iter().map(move |l| (i, l.t)))
+        .filter_map(|(i, t)| index.get(&t).map(|&j| (i, j)))
+        .collect()
+}
+
+/**
+Updates the link coordinates to the new positions of the Nodes.
+ */
+pub(crate) fn relink(nodes: &mut [Node]) {
+    let geos = nodes
+        .iter()
+        .map(|n| (n.hash, n.position()))
+        .collect::<HashMap<_, _>>();
+    for node in nodes.iter_mut() {
+        let geo = node.position();
+        for link in node.links.iter_mut() {
+            link.from = Some(geo);
+            if let Some(to) = geos.get(&link.t) {
+                link.to = Some(*to);
+            }
+        }
+    }
+}
+
+/**
+Returns the difference between a and b and their distance, separating elements sharing a position.
+ */
+fn delta(a: (f64, f64), b: (f64, f64), rng: &mut StdRng) -> (f64, f64, f64) {
+    let (mut dx, mut dy) = (a.0 - b.0, a.1 - b.1);
+    if dx == 0.0 && dy == 0.0 {
+        dx = rng.gen_range(-0.5..0.5);
+        dy = rng.gen_range(-0.5..0.5);
+    }
+    (dx, dy, (dx * dx + dy * dy).sqrt().max(0.01))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn two_clusters() -> Vec<Node> {
+        let mut nodes = Node::from_list(&[(0, 0); 8]);
+        for (a, b) in [
+            (0, 1),
+            (1, 2),
+            (2, 3),
+            (3, 0),
+            (4, 5),
+            (5, 6),
+            (6, 7),
+            (7, 4),
+        ]
+        .iter()
+        {
+            let other = nodes[*b].clone();
+            nodes[*a].link(&other);
+        }
+        nodes
+    }
+
+    fn distance(a: &Node, b: &Node) -> u32 {
+        coordinate::distance(a.position(), b.position())
+    }
+
+    #[test]
+    fn deterministic() {
+        let layout = ForceDirected::default();
+        let mut a = two_clusters();
+        let mut b = two_clusters();
+        layout.layout(&mut a);
+        layout.layout(&mut b);
+        assert_eq!(a, b);
+        assert!(a.iter().zip(b.iter()).all(|(a, b)| a.geo == b.geo));
+
+        let mut c = two_clusters();
+        ForceDirected { seed: 1, ..layout }.layout(&mut c);
+        assert!(a.iter().zip(c.iter()).any(|(a, c)| a.geo != c.geo));
+    }
+
+    #[test]
+    fn within_bounds() {
+        let layout = ForceDirected::new(coordinate!(-50, 100), coordinate!(50, 150));
+        let mut nodes = two_clusters();
+        layout.layout(&mut nodes);
+        for node in nodes.iter() {
+            assert!(node.geo.x >= -50.0 && node.geo.x <= 50.0, "{}", node.geo);
+            assert!(node.geo.y >= 100.0 && node.geo.y <= 150.0, "{}", node.geo);
+        }
+    }
+
+    #[test]
+    fn swapped_bounds() {
+        let mut nodes = two_clusters();
+        ForceDirected::new(coordinate!(100, 0), coordinate!(0, 100)).layout(&mut nodes);
+        let mut swapped = two_clusters();
+        ForceDirected::new(coordinate!(0, 100), coordinate!(100, 0)).layout(&mut swapped);
+        assert!(nodes
+            .iter()
+            .zip(swapped.iter())
+            .all(|(a, b)| a.geo == b.geo));
+        for node in swapped.iter() {
+            assert!(node.geo.x >= 0.0 && node.geo.x <= 100.0, "{}", node.geo);
+            assert!(node.geo.y >= 0.0 && node.geo.y <= 100.0, "{}", node.geo);
+        }
+    }
+
+    #[test]
+    fn linked_nodes_are_closer() {
+        let mut nodes = two_clusters();
+        ForceDirected::new(coordinate!(), coordinate!(1000, 1000)).layout(&mut nodes);
+        let linked = distance(&nodes[0], &nodes[1]) + distance(&nodes[4], &nodes[5]);
+        let unlinked = distance(&nodes[0], &nodes[4]) + distance(&nodes[1], &nodes[5]);
+        assert!(linked < unlinked, "{} >= {}", linked, unlinked);
+    }
+
+    #[test]
+    fn links_follow() {
+        let mut nodes = two_clusters();
+        ForceDirected::default().layout(&mut nodes);
+        let link = nodes[0].hl(0).unwrap();
+        assert_eq!(link.from, Some(nodes[0].position()));
+        assert_eq!(link.to, Some(nodes[1].position()));
+    }
+
+    #[test]
+    fn anchors_stay() {
+        let layout = ForceDirected::new(coordinate!(), coordinate!(100, 100));
+        let mut nodes = two_clusters();
+        nodes[0].geo = Point::new(500.0, -20.0);
+        nodes[4].geo = Point::new(50.0, 50.0);
+        layout.layout_anchored(&mut nodes, &[0, 4, 99]);
+        assert_eq!(nodes[0].geo, Point::new(500.0, -20.0));
+        assert_eq!(nodes[4].geo, Point::new(50.0, 50.0));
+        for node in nodes.iter().filter(|n| n.geo != nodes[0].geo) {
+            assert!(node.geo.x >= 0.0 && node.geo.x <= 100.0, "{}", node.geo);
+            assert!(node.geo.y >= 0.0 && node.geo.y <= 100.0, "{}", node.geo);
+        }
+        assert_eq!(nodes[3].hl(0).unwrap().to, Some(nodes[0].position()));
+
+        // Without anchors, every Node is moved like layout does.
+        let mut all = two_clusters();
+        layout.layout_anchored(&mut all, &[]);
+        let mut moved = two_clusters();
+        layout.layout(&mut moved);
+        assert!(all.iter().zip(moved.iter()).all(|(a, b)| a.geo == b.geo));
+    }
+
+    #[test]
+    fn empty_and_single() {
+        let layout = ForceDirected::default();
+        layout.layout(&mut []);
+        let mut nodes = Node::from_list(&[(-100, -100)]);
+        layout.layout(&mut nodes);
+        assert!(nodes[0].geo.x >= 0.0 && nodes[0].geo.y >= 0.0);
+    }
+}
@@ -14,14 +14,20 @@ pub mod macros;
 pub mod consts;
 pub mod coordinate;
 pub mod data;
+pub mod error;
 pub mod group;
+pub mod layout;
 pub mod map;
 pub mod node;
 pub mod tools;
 pub mod traits;
 
+pub use error::Error;
 pub use traits::*;
 
+use rand::{rngs::StdRng, RngCore, SeedableRng};
+use std::{collections::HashMap, sync::Arc};
+
 #[cfg(test)]
 mod tests;
 
@@ -34,8 +40,20 @@ differennt structures to be drawn.
  */
 #[derive(Debug, Eq, Copy, Clone, Default)]
 pub struct Coordinate {
-    pub x: i16,
-    pub y: i16,
+    pub x: i32,
+    pub y: i32,
+}
+
+/*
+Holds a world position on a x and y plane.
+
+Used for layout and geometry, so repeated transformations do not accumulate
+rounding errors. It is rounded to a pixel Coordinate when drawn.
+ */
+#[derive(Debug, PartialEq, Copy, Clone, Default)]
+pub struct Point {
+    pub x: f64,
+    pub y: f64,
 }
 
 /*
@@ -53,6 +71,28 @@ This means that B does not know that A is connected to it.
 
 Deleting node B in this scenario would impact drawing and pathing.
 But does not have any direct impact on the HL since it only stores a Hash reference to the node it is linked to.
+
+
+## Weight
+
+The cost of traversing the HL when pathing. When it is not set, the distance
+between the two nodes is used instead.
+
+
+## Paint and width
+
+How the HL is colored and how many pixels wide it is drawn. When they are not set,
+the edge Paint and link width of the Map's Theme are used instead.
+
+
+## Arrow
+
+An arrowhead drawn at the end of the HL linked to, pointing along the direction the edge arrives in.
+
+
+## Stroke
+
+Whether the HL is drawn as a solid, dashed or dotted line.
  */
 #[derive(Copy, PartialEq, Eq, Clone, Debug, Default)]
 pub struct HL {
@@ -61,18 +101,29 @@ pub struct HL {
     pub t: u64,
     pub from: Option<Coordinate>,
     pub to: Option<Coordinate>,
+    pub weight: Option<u32>,
+    pub paint: Option<map::theme::Paint>,
+    pub width: Option<u32>,
+    pub arrow: Option<Arrow>,
+    pub stroke: Stroke,
 }
 
 /**
 A Location object that can be drawn on an image, along with set size and color.
+
+Nodes without a color are drawn in the node color of the Map's Theme.
+Nodes with a label have it drawn next to them.
+Nodes with a shape are drawn as it, instead of the shape given to the Map.
  */
-#[derive(Copy, Clone, Debug)]
+#[derive(Clone, Debug)]
 pub struct Node {
     pub hash: u64,
-    pub geo: Coordinate,
-    pub color: image::Rgb<u8>,
+    pub geo: Point,
+    pub color: Option<image::Rgba<u8>>,
     pub radius: Option<u32>,
-    links: [HL; consts::MAX_LINKS],
+    pub label: Option<String>,
+    pub shape: Option<Shape>,
+    links: Vec<HL>,
 }
 
 /**
@@ -86,6 +137,7 @@ It contains a Node used for Group meta data.
 pub struct Group {
     settings: Node,
     pub nodes: Vec<Node>,
+    rng: Option<StdRng>,
 }
 
 /**
@@ -94,7 +146,18 @@ High abstraction Map which helps position objects.
 #[derive(Clone, Debug, Default)]
 pub struct Map {
     image: Option<IW>,
+    svg: Option<map::svg::Svg>,
+    vector: bool,
     add: Coordinate,
+    size: Option<(u32, u32)>,
+    view: Option<(Point, f64)>,
+    padding: u32,
+    fit: bool,
+    rasterizer: map::raster::Rasterizer,
+    composite: map::composite::Composite,
+    theme: map::theme::Theme,
+    placement: map::label::Placement,
+    layers: Vec<map::Layer>,
 }
 
 /**
@@ -103,9 +166,9 @@ Enables traversing through a network of connected nodes.
 
 Checking if a path is valid and setting new paths.
  */
-#[derive(Clone, Copy)]
-pub struct Network<T: Draw + Hash + std::marker::Copy> {
-    pub hash_map: [Option<T>; consts::NETWORK_REM],
+#[derive(Clone, Debug)]
+pub struct Network<T: Draw + Hash> {
+    pub hash_map: HashMap<u64, T>,
 }
 
 // ------------------------------------------------------------------
@@ -129,6 +192,17 @@ Curves the connection
 
 Uses Brasehem's line algorithm to directly correct the nodes.
 
+
+## Bezier
+
+A cubic Bezier curve, bowing to the left of the direction it is drawn in by the curvature,
+as a percentage of the distance between the nodes. A negative curvature bows to the right.
+
+
+## Orthogonal
+
+Horizontal and vertical lines, bending around the other nodes drawn on the Map.
+
  */
 #[derive(Copy, PartialEq, Eq, Clone, Debug, Default)]
 pub enum EdgeStyle {
@@ -136,18 +210,101 @@ pub enum EdgeStyle {
     Direct,
     Ellipse,
     Straight,
+    Bezier(i32),
+    Orthogonal,
+}
+
+/**
+The pattern Edges are stroked with.
+
+
+## Dashed
+
+Dashes twice as long as the gaps between them, scaled by the width of the edge.
+
+
+## Dotted
+
+Dots as wide as the edge, spaced by the width of the edge.
+
+ */
+#[derive(Copy, PartialEq, Eq, Clone, Debug, Default)]
+pub enum Stroke {
+    #[default]
+    Solid,
+    Dashed,
+    Dotted,
+}
+
+impl Stroke {
+    /**
+    Returns the length of the drawn and the skipped parts of the pattern for an edge with the width, if it has a pattern.
+
+
+    ## Examples
+
+    ```
+    # use pathtracer::Stroke;
+    assert_eq!(Stroke::Solid.pattern(2), None);
+    assert_eq!(Stroke::Dashed.pattern(2), Some((8, 4)));
+    assert_eq!(Stroke::Dotted.pattern(2), Some((2, 2)));
+    ```
+     */
+    pub fn pattern(&self, width: u32) -> Option<(u32, u32)> {
+        let width = std::cmp::max(width, 1);
+        match self {
+            Stroke::Solid => None,
+            Stroke::Dashed => Some((4 * width, 2 * width)),
+            Stroke::Dotted => Some((width, width)),
+        }
+    }
+}
+
+/**
+Arrowhead styles for Edges, drawn where the edge arrives at the Node it links to.
+
+
+## Filled
+
+A solid triangle.
+
+
+## Open
+
+Two lines meeting at the tip, like a V on its side.
+
+ */
+#[derive(Copy, PartialEq, Eq, Clone, Debug)]
+pub enum Arrow {
+    Filled,
+    Open,
 }
 
 /**
  Creates a shape of coordinate points.
 
  Sampled from a Area.
+
+
+ ## Ring
+
+ A circle with a hole in the middle, half the radius wide.
+
+
+ ## Custom
+
+ Drawn by the CustomShape implementation, which decides how it is rasterized.
 */
 #[derive(Debug, Clone)]
 pub enum Shape {
     Circle,
     Square,
     Triangle,
+    Diamond,
+    Hexagon,
+    Star,
+    Ring,
+    Custom(Arc<dyn CustomShape>),
 }
 
 // ------------------------------------------------------------------
@@ -165,17 +322,93 @@ impl Shape {
 
     */
     pub fn area(&self, area: usize) -> Vec<Coordinate> {
-        match *self {
+        match self {
             Shape::Circle => Shape::circle_area(area),
             Shape::Square => Shape::square_area(area),
             Shape::Triangle => Shape::triangle_area(area),
+            Shape::Ring => Shape::ring_area(area),
+            Shape::Custom(custom) => custom.area(area as u32),
+            _ => {
+                let corners = self.corners(area as f64).unwrap_or_default();
+                let center = |c: Coordinate| Point::from(c) + Point::new(0.5, 0.5);
+                (0..area as i32)
+                    .flat_map(|x| (0..area as i32).map(move |y| coordinate!(x, y)))
+                    .filter(|&c| tools::inside(&corners, center(c)))
+                    .collect()
+            }
+        }
+    }
+
+    /**
+    Returns the corners of the shapes which are polygons, relative to the top-left of the node.
+
+
+    ## Examples
+
+    ```
+    # use pathtracer::*;
+    let corners = Shape::Diamond.corners(4.0).unwrap();
+    assert_eq!(corners[0], Point::new(2.0, 0.0));
+    assert!(Shape::Circle.corners(4.0).is_none());
+    ```
+     */
+    pub fn corners(&self, size: f64) -> Option<Vec<Point>> {
+        let half = size / 2.0;
+        let around = |points: usize, radius: &dyn Fn(usize) -> f64| {
+            (0..points)
+                .map(|i| {
+                    let angle = std::f64::consts::PI * 2.0 * i as f64 / points as f64
+                        - std::f64::consts::FRAC_PI_2;
+                    let r = radius(i);
+                    Point::new(half + r * angle.cos(), half + r * angle.sin())
+                })
+                .collect()
+        };
+        match self {
+            Shape::Triangle => Some(vec![
+                Point::new(half, 0.0),
+                Point::new(0.0, size),
+                Point::new(size, size),
+            ]),
+            Shape::Diamond => Some(vec![
+                Point::new(half, 0.0),
+                Point::new(size, half),
+                Point::new(half, size),
+                Point::new(0.0, half),
+            ]),
+            Shape::Hexagon => Some(around(6, &|_| half)),
+            Shape::Star => Some(around(10, &|i| if i % 2 == 0 { half } else { half * 0.4 })),
+            _ => None,
+        }
+    }
+
+    /**
+    Returns the top left and bottom right corner of the area the shape drawn at the position covers.
+     */
+    fn extent(&self, pos: Coordinate, size: u32) -> (Coordinate, Coordinate) {
+        match self {
+            Shape::Circle | Shape::Ring => (pos - coordinate!(size), pos + coordinate!(size)),
+            _ => (pos, pos + coordinate!(size)),
         }
     }
 
+    fn ring_area(area: usize) -> Vec<Coordinate> {
+        let outer = area as f64 - 0.5;
+        let inner = outer / 2.0;
+        let r = area as i32;
+        (-r..=r)
+            .flat_map(|x| (-r..=r).map(move |y| coordinate!(x, y)))
+            .filter(|c| {
+                let d = f64::from(c.x).hypot(f64::from(c.y));
+                inner <= d && d <= outer
+            })
+            .collect()
+    }
+
     fn circle_area(area: usize) -> Vec<Coordinate> {
         let mut vec = Vec::new();
         let mut pos = coordinate!((area - 1), 0);
-        let mut err: i16 = 1 - (area << 1) as i16;
+        let mut err: i32 = 1 - (area << 1) as i32;
         let mut d = Coordinate::new(err, 1);
 
         let q_plot = |x1, y1, x2, y2| tools::plot(coordinate!(x1, y1), coordinate!(x2, y2));
@@ -253,6 +486,12 @@ impl std::fmt::Display for Coordinate {
     }
 }
 
+impl std::fmt::Display for Point {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}, {}", self.x, self.y)
+    }
+}
+
 impl std::fmt::Display for HL {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         write!(f, "{} -> {}", self.f, self.t)
@@ -266,24 +505,139 @@ Image wrapper around the Image crate to enable better debugging panics.
  */
 #[derive(Clone, Debug)]
 pub struct IW {
-    img: image::ImageBuffer<image::Rgb<u8>, Vec<u8>>,
+    img: image::ImageBuffer<image::Rgba<u8>, Vec<u8>>,
+    viewport: map::Viewport,
+    rasterizer: map::raster::Rasterizer,
+    composite: map::composite::Composite,
+    theme: map::theme::Theme,
+    labels: map::label::Labels,
+    obstacles: Vec<(Coordinate, Coordinate)>,
+    sizes: HashMap<u64, u32>,
 }
 
 impl IW {
     /**
     Retrieves the private image field.
      */
-    pub fn image(&self) -> &image::ImageBuffer<image::Rgb<u8>, Vec<u8>> {
+    pub fn image(&self) -> &image::ImageBuffer<image::Rgba<u8>, Vec<u8>> {
         &self.img
     }
 
     /**
-    Wraps around Image put_pixel but indicates failed positions.
+    Draws the color on the pixel, composited with what is already drawn. Positions outside of the image are clipped.
+
+
+    ## Examples
+
+    ```
+    # use pathtracer::{map::composite::Composite, *};
+    let mut image = Map::new().size(10, 10).composite(Composite::Add).map::<Node>(&[]).consume();
+    let heat = image::Rgba([100, 0, 0, 255]);
+    image.put(&Coordinate::new(2, 2), heat);
+    image.put(&Coordinate::new(2, 2), heat);
+    assert_eq!(image.image().get_pixel(2, 2).0, [200, 0, 0, 255]);
+    ```
+     */
+    pub fn put<L: Location>(&mut self, l: &L, color: image::Rgba<u8>) {
+        let (x, y) = (l.x(), l.y());
+        if x < 0 || y < 0 || x as u32 >= self.img.width() || y as u32 >= self.img.height() {
+            return;
+        }
+        let pixel = self.img.get_pixel_mut(x as u32, y as u32);
+        *pixel = self.composite.apply(*pixel, color);
+    }
+
+    /**
+    Draws the color with its opacity scaled by coverage, between 0 and 1.
+     */
+    pub fn blend<L: Location>(&mut self, l: &L, mut color: image::Rgba<u8>, coverage: f64) {
+        color.0[3] = (f64::from(color.0[3]) * coverage.clamp(0.0, 1.0)).round() as u8;
+        self.put(l, color);
+    }
+
+    /**
+    Draws each pixel once, using the last color given for a position.
+
+    Shapes made of overlapping plots would otherwise be composited with themselves.
+     */
+    fn paint(&mut self, pixels: Vec<(Coordinate, image::Rgba<u8>)>) {
+        let unique = pixels
+            .into_iter()
+            .map(|(c, color)| ((c.x, c.y), color))
+            .collect::<HashMap<_, _>>();
+        for ((x, y), color) in unique {
+            self.put(&coordinate!(x, y), color);
+        }
+    }
+
+    /**
+    Returns the pixel the world position is drawn at.
+     */
+    pub fn project(&self, p: Point) -> Coordinate {
+        self.viewport.project(p)
+    }
+
+    /**
+    Returns how shapes and lines are drawn on the image.
+     */
+    pub fn rasterizer(&self) -> map::raster::Rasterizer {
+        self.rasterizer
+    }
+
+    /**
+    Returns how colors are combined with the pixels they are drawn on.
+     */
+    pub fn composite(&self) -> map::composite::Composite {
+        self.composite
+    }
+
+    /**
+    Returns the Theme elements are drawn with.
+     */
+    pub fn theme(&self) -> &map::theme::Theme {
+        &self.theme
+    }
+
+    /**
+    Draws the text next to the area between min and max, where it does not overlap other labels.
+     */
+    pub fn label(&mut self, min: Coordinate, max: Coordinate, text: &str) {
+        let size = map::label::size(text);
+        if let Some(pos) = self.labels.place(min, max, size, self.dimensions()) {
+            let color = self.theme.label;
+            map::label::text(self, pos, text, color);
+        }
+    }
+
+    /**
+    Marks the area between min and max as taken, so Orthogonal edges are routed around it.
+     */
+    pub fn avoid(&mut self, min: Coordinate, max: Coordinate) {
+        self.obstacles.push((min, max));
+    }
+
+    /**
+    Returns the areas Orthogonal edges are routed around.
+     */
+    pub fn obstacles(&self) -> &[(Coordinate, Coordinate)] {
+        &self.obstacles
+    }
+
+    /**
+    Records the size of the element with the hash, so arrowheads pointing at it stop at its edge.
+     */
+    pub fn sized(&mut self, hash: u64, size: u32) {
+        self.sizes.insert(hash, size);
+    }
 
-    Set debug_assertions flag to panic for out of bounds positions with improved debugging messages.
+    /**
+    Returns the size of the element with the hash, or the default Node size if it has not been recorded.
      */
-    pub fn put<L: Location>(&mut self, l: &L, color: image::Rgb<u8>) {
-        self.img.put_pixel(l.x() as u32, l.y() as u32, color);
+    pub fn size_of(&self, hash: u64) -> u32 {
+        self.sizes
+            .get(&hash)
+            .copied()
+            .unwrap_or_else(|| u32::from(consts::DEFAULT_SIZE))
     }
 
     /**
@@ -325,7 +679,7 @@ impl MinMax for Node {
         let mut max = coordinate!(consts::DEFAULT_SIZE);
         let mut min = self.position();
         min -= max;
-        max += self.geo;
+        max += self.position();
         (min, max)
     }
 }
@@ -389,8 +743,25 @@ impl Location for Coordinate {
     }
 }
 
+impl Location for Point {
+    /**
+    Rounds the Point to the nearest pixel.
+     */
+    fn position(&self) -> Coordinate {
+        coordinate!(self.x.round(), self.y.round())
+    }
+}
+
 // ------------------------------------------------------------------
 
+/**
+Returns the area an element drawn at the pixel covers, for edges to be routed around.
+ */
+fn obstacle<T: Draw>(element: &T, pixel: Coordinate) -> (Coordinate, Coordinate) {
+    let size = coordinate!(element.size());
+    (pixel - size, pixel + size)
+}
+
 impl Draw for Node {
     /**
     Draws the node on an IW.
@@ -399,26 +770,76 @@ impl Draw for Node {
     But instead use the Map struct, which uses this trait implementation.
     */
     fn draw(&self, mut image: IW, offset: Coordinate, shape: &Shape) -> IW {
-        let s = consts::DEFAULT_LINK_SIZE / 2;
-        let pos = self.geo + offset - coordinate!(s, s);
+        let shape = self.shape.as_ref().unwrap_or(shape);
+        let theme = *image.theme();
+        let color = self.color.unwrap_or(theme.node);
+        let s = theme.link_width / 2;
+        let pos = image.project(self.geo + Point::from(offset)) - coordinate!(s, s);
 
         for link in &self.links {
-            image = link.draw(image, offset, u32::from(consts::DEFAULT_LINK_SIZE));
+            let target = image.size_of(link.t);
+            image = link.draw(image, offset, theme.link_width, target);
         }
 
-        for o in shape.area(self.size() as usize) {
-            let color = if o.x == 0 || o.y == 0 {
-                let c = self
-                    .color
-                    .0
-                    .iter()
-                    .map(|x| x.saturating_add(consts::DEFAULT_SHADE as u8))
-                    .collect::<Vec<_>>();
-                image::Rgb([c[0], c[1], c[2]])
-            } else {
-                self.color
-            };
-            image.put(&(pos + o), color);
+        let size = self.size();
+        if image.rasterizer() == map::raster::Rasterizer::AntiAliased {
+            let corner = Point::from(pos);
+            match shape {
+                Shape::Circle => {
+                    map::raster::circle(&mut image, corner, f64::from(size) - 0.5, color)
+                }
+                Shape::Ring => {
+                    let outer = f64::from(size) - 0.5;
+                    map::raster::ring(&mut image, corner, outer, outer / 2.0, color)
+                }
+                Shape::Triangle => {
+                    let size = f64::from(size);
+                    let corners = [
+                        corner + Point::new(size / 2.0, 0.0),
+                        corner + Point::new(0.0, size),
+                        corner + Point::new(size, size),
+                    ];
+                    map::raster::triangle(&mut image, corners, color)
+                }
+                Shape::Diamond | Shape::Hexagon | Shape::Star => {
+                    let corners = shape
+                        .corners(f64::from(size))
+                        .unwrap_or_default()
+                        .into_iter()
+                        .map(|c| corner + c)
+                        .collect::<Vec<_>>();
+                    map::raster::polygon(&mut image, &corners, color)
+                }
+                Shape::Custom(custom) => custom.draw(&mut image, pos, size, color),
+                Shape::Square => image.paint(
+                    shape
+                        .area(size as usize)
+                        .into_iter()
+                        .map(|o| (pos + o, color))
+                        .collect(),
+                ),
+            }
+        } else if let Shape::Custom(custom) = shape {
+            custom.draw(&mut image, pos, size, color);
+        } else {
+            let border = theme.outline(color);
+            let pixels = shape
+                .area(size as usize)
+                .into_iter()
+                .map(|o| {
+                    if o.x == 0 || o.y == 0 {
+                        (pos + o, border)
+                    } else {
+                        (pos + o, color)
+                    }
+                })
+                .collect();
+            image.paint(pixels);
+        }
+
+        if let Some(label) = &self.label {
+            let (min, max) = shape.extent(pos, size);
+            image.label(min, max, label);
         }
         image
     }
@@ -431,6 +852,26 @@ impl Draw for Node {
     fn links(&self) -> &[HL] {
         &self.links
     }
+
+    fn draw_svg(&self, svg: &mut map::svg::Svg, offset: Coordinate, shape: &Shape) {
+        let shape = self.shape.as_ref().unwrap_or(shape);
+        let theme = *svg.theme();
+        let s = theme.link_width / 2;
+        for link in &self.links {
+            let target = svg.size_of(link.t);
+            link.draw_svg(svg, offset, theme.link_width, target);
+        }
+        let pos = svg.project(self.geo + Point::from(offset)) - coordinate!(s, s);
+        svg.shape(shape, pos, self.size(), self.color.unwrap_or(theme.node));
+        if let Some(label) = &self.label {
+            let (min, max) = shape.extent(pos, self.size());
+            svg.label(min, max, label);
+        }
+    }
+
+    fn snapshot(&self) -> Option<map::Snapshot> {
+        Some(map::Snapshot::Node(self.clone()))
+    }
 }
 
 impl Draw for Group {
@@ -439,11 +880,22 @@ impl Draw for Group {
 
     If none the Group is draw as blank.
      */
-    fn draw(&self, image: IW, mut offset: Coordinate, shape: &Shape) -> IW {
+    fn draw(&self, mut image: IW, mut offset: Coordinate, shape: &Shape) -> IW {
+        let shape = self.settings.shape.as_ref().unwrap_or(shape);
         offset += self.position();
-        self.nodes
+        for node in &self.nodes {
+            image.sized(node.hash, node.size());
+        }
+        let mut image = self
+            .nodes
             .iter()
-            .fold(image, |acc, node| node.draw(acc, offset, shape))
+            .fold(image, |acc, node| node.draw(acc, offset, shape));
+        if let Some(label) = &self.settings.label {
+            let center = image.project(Point::from(offset));
+            let r = coordinate!(self.dynamic_radius());
+            image.label(center - r, center + r, label);
+        }
+        image
     }
 
     fn size(&self) -> u32 {
@@ -460,6 +912,31 @@ impl Draw for Group {
     fn links(&self) -> &[HL] {
         self.settings.links()
     }
+
+    /**
+    Writes the Nodes inside the Group as a layer.
+     */
+    fn draw_svg(&self, svg: &mut map::svg::Svg, mut offset: Coordinate, shape: &Shape) {
+        let shape = self.settings.shape.as_ref().unwrap_or(shape);
+        offset += self.position();
+        svg.group_start(self.hash());
+        for node in &self.nodes {
+            svg.sized(node.hash, node.size());
+        }
+        for node in &self.nodes {
+            node.draw_svg(svg, offset, shape);
+        }
+        if let Some(label) = &self.settings.label {
+            let center = svg.project(Point::from(offset));
+            let r = coordinate!(self.dynamic_radius());
+            svg.label(center - r, center + r, label);
+        }
+        svg.group_end();
+    }
+
+    fn snapshot(&self) -> Option<map::Snapshot> {
+        Some(map::Snapshot::Group(Box::new(self.clone())))
+    }
 }
 
 // ------------------------------------------------------------------
@@ -506,6 +983,12 @@ impl From<Group> for Coordinate {
     }
 }
 
+impl From<Coordinate> for Point {
+    fn from(c: Coordinate) -> Self {
+        Point::new(f64::from(c.x), f64::from(c.y))
+    }
+}
+
 // ------------------------------------------------------------------
 
 impl Coordinate {
@@ -542,7 +1025,7 @@ impl Coordinate {
     # }
     ```
      */
-    pub fn new(x: i16, y: i16) -> Self {
+    pub fn new(x: i32, y: i32) -> Self {
         Coordinate { x, y }
     }
 
@@ -558,7 +1041,7 @@ impl Coordinate {
     assert!(c.lt(11));
     ```
      */
-    pub fn lt(self, lt: i16) -> bool {
+    pub fn lt(self, lt: i32) -> bool {
         self.x < lt || self.y < lt
     }
 
@@ -581,24 +1064,46 @@ impl Coordinate {
     /**
     Creates a list of coordinates from a list of tuples with x and y positions.
      */
-    pub fn from_list(list: &[(i16, i16)]) -> Vec<Coordinate> {
+    pub fn from_list(list: &[(i32, i32)]) -> Vec<Coordinate> {
         coordinate::from_list(list, &|c, _i| c)
     }
 }
 
+impl Point {
+    /**
+    Constructs a Point struct.
+
+
+    ## Examples
+
+    ```
+    # use pathtracer::*;
+    let p = Point::new(10.4, -0.6);
+    assert_eq!(p.position(), Coordinate::new(10, -1));
+    assert_eq!(Point::from(Coordinate::new(1, 2)), Point::new(1.0, 2.0));
+    ```
+     */
+    pub fn new(x: f64, y: f64) -> Self {
+        Point { x, y }
+    }
+}
+
 impl Node {
     /**
     Constructs a Node struct.
 
     The name is converted from a &str to a hash.
+    The position can be given as either a pixel Coordinate or a Point.
      */
-    pub fn new(name: &str, geo: Coordinate) -> Self {
+    pub fn new<P: Into<Point>>(name: &str, geo: P) -> Self {
         Node {
             hash: data::calculate_hash(&name),
-            geo,
-            color: consts::DEFAULT_RGBA,
+            geo: geo.into(),
+            color: None,
             radius: None,
-            links: [HL::new(0, 0); consts::MAX_LINKS],
+            label: None,
+            shape: None,
+            links: Vec::new(),
         }
     }
 
@@ -625,7 +1130,7 @@ impl Node {
     assert_eq!(nodes.len(), 3);
     ```
      */
-    pub fn from_file(path: &str) -> Result<Vec<Self>, std::io::Error> {
+    pub fn from_file(path: &str) -> Result<Vec<Self>, Error> {
         node::from_file(path)
     }
 
@@ -637,6 +1142,41 @@ impl Node {
         self.position() + half
     }
 
+    /**
+    Sets the label drawn next to the Node.
+
+
+    ## Examples
+
+    ```
+    # use pathtracer::*;
+    let mut node = Node::new("A", Coordinate::new(0, 0));
+    node.label("Start");
+    assert_eq!(node.label.as_deref(), Some("Start"));
+    ```
+     */
+    pub fn label(&mut self, label: &str) {
+        self.label = Some(label.to_string());
+    }
+
+    /**
+    Sets the shape the Node is drawn as, regardless of the shape given to the Map.
+
+
+    ## Examples
+
+    ```
+    # use pathtracer::*;
+    let mut nodes = Node::from_list(&[(0, 0), (20, 0)]);
+    nodes[1].shape(Shape::Star);
+    // The first Node is drawn as a circle, the second as a star.
+    let map = Map::new().map_shape(&nodes, &Shape::Circle);
+    ```
+     */
+    pub fn shape(&mut self, shape: Shape) {
+        self.shape = Some(shape);
+    }
+
     /**
     Converts a list of tuples (x,y) to a Vector of Nodes.
 
@@ -662,7 +1202,7 @@ impl Node {
     assert_eq!(nodes.len(), 0);
     ```
      */
-    pub fn from_list(list: &[(i16, i16)]) -> Vec<Self> {
+    pub fn from_list(list: &[(i32, i32)]) -> Vec<Self> {
         coordinate::from_list(list, &|c, i| {
             Node::new(&std::char::from_u32(65 + i as u32).unwrap().to_string(), c)
         })
@@ -737,14 +1277,13 @@ impl Node {
     You can only retrieve HL which are connected to other nodes.
 
      */
-    pub fn hl(&self, index: usize) -> std::io::Result<&HL> {
-        if index > self.get_link_avail_index() || !self.links[index].is_connected() {
-            Err(std::io::Error::new(
-                std::io::ErrorKind::Other,
-                "index too large",
-            ))
-        } else {
-            Ok(&self.links[index])
+    pub fn hl(&self, index: usize) -> Result<&HL, Error> {
+        match self.links.get(index) {
+            Some(link) if link.is_connected() => Ok(link),
+            _ => Err(Error::LinkIndexOutOfRange {
+                index,
+                len: self.links.len(),
+            }),
         }
     }
 
@@ -758,8 +1297,8 @@ impl Node {
 
     ```
     # #[macro_use] extern crate pathtracer;
-    # use pathtracer::{EdgeStyle, Coordinate, Node};
-    # fn main() -> std::io::Result<()> {
+    # use pathtracer::{EdgeStyle, Coordinate, Error, Node};
+    # fn main() -> Result<(), Error> {
     let mut a = node!("A", 0, 0);
     let mut b = node!("B", 50, 50);
     b.link(&a);
@@ -779,20 +1318,11 @@ impl Node {
     You can only retrieve HL which are connected to other nodes.
 
      */
-    pub fn hl_mut(&mut self, index: usize) -> std::io::Result<&mut HL> {
-        if index > self.get_link_avail_index() || !self.links[index].is_connected() {
-            Err(std::io::Error::new(
-                std::io::ErrorKind::Other,
-                format!(
-                    "index {} too large or not connected. Index: {} expected. Connection status: \
-                     {}",
-                    index,
-                    self.get_link_avail_index(),
-                    self.links[index].is_connected()
-                ),
-            ))
-        } else {
-            Ok(&mut self.links[index])
+    pub fn hl_mut(&mut self, index: usize) -> Result<&mut HL, Error> {
+        let len = self.links.len();
+        match self.links.get_mut(index) {
+            Some(link) if link.is_connected() => Ok(link),
+            _ => Err(Error::LinkIndexOutOfRange { index, len }),
         }
     }
 
@@ -835,22 +1365,21 @@ impl Node {
         let mut prev = coordinate!();
         let mut prev_h = 0;
         for node in &mut list {
-            if prev_h != 0 && f(prev, node.geo) {
+            if prev_h != 0 && f(prev, node.position()) {
                 let mut link = HL::new(node.hash, prev_h);
                 link.to = Some(prev);
-                link.from = Some(node.geo);
-                let i = node.get_link_avail_index();
-                node.links[i] = link;
+                link.from = Some(node.position());
+                node.links.push(link);
             }
 
             prev_h = node.hash();
-            prev = node.geo;
+            prev = node.position();
         }
         list
     }
 
     /**
-    Returns the next point which is available to link.
+    Returns the index the next link will be stored at, which is also the number of links.
 
     It is a good practice to call this method before attempting to call hl or hl_mut to having to error handle.
 
@@ -886,10 +1415,7 @@ impl Node {
     ```
      */
     pub fn get_link_avail_index(&self) -> usize {
-        self.links()
-            .iter()
-            .position(|x| !x.is_connected())
-            .unwrap_or(consts::MAX_LINKS - 1)
+        self.links.len()
     }
 
     /**
@@ -926,7 +1452,7 @@ impl Node {
 
      */
     pub fn disconnect(&mut self) {
-        self.links = [HL::new(0, 0); consts::MAX_LINKS];
+        self.links.clear();
     }
 
     /**
@@ -960,14 +1486,35 @@ impl Node {
 
      */
     pub fn link<P: Hash + Location>(&mut self, other: &P) {
-        let i = self.get_link_avail_index();
-        self.links[i] = HL {
-            style: EdgeStyle::default(),
-            f: self.hash,
-            t: other.hash(),
-            from: Some(self.geo),
+        self.links.push(HL {
+            from: Some(self.position()),
             to: Some(other.position()),
-        };
+            ..HL::new(self.hash, other.hash())
+        });
+    }
+
+    /**
+    Links Node self to another point with a weight used as the cost when pathing.
+
+
+    ## Examples
+
+    ```
+    # #[macro_use] use pathtracer::*;
+    # fn main() -> Result<(), Error> {
+    let b = node!("B", 10, 10);
+    let mut a = node!("A", 0, 0);
+    a.link_weighted(&b, 60);
+    assert_eq!(a.hl(0)?.weight, Some(60));
+    # Ok(())
+    # }
+    ```
+     */
+    pub fn link_weighted<P: Hash + Location>(&mut self, other: &P, weight: u32) {
+        self.link(other);
+        if let Some(link) = self.links.last_mut() {
+            link.weight(weight);
+        }
     }
 }
 
@@ -979,6 +1526,11 @@ impl HL {
             t,
             from: None,
             to: None,
+            weight: None,
+            paint: None,
+            width: None,
+            arrow: None,
+            stroke: Stroke::Solid,
         }
     }
 
@@ -994,7 +1546,7 @@ impl HL {
 
     ```
     # #[macro_use] use pathtracer::*;
-    # fn main() -> std::io::Result<()> {
+    # fn main() -> Result<(), Error> {
     let b = cluster!();
     let mut a = node!();
     a.link(&b);
@@ -1009,18 +1561,120 @@ impl HL {
     }
 
     /**
-    Checks if the HL has two endpoint hashes.
+    Sets the cost of traversing the edge when pathing.
+
+    Without a weight the distance between the connected nodes is used.
 
 
     ## Examples
 
     ```
     # #[macro_use] use pathtracer::*;
-    # fn main() -> std::io::Result<()> {
-    let b = cluster!();
-    let mut a = node!();
+    # fn main() -> Result<(), Error> {
+    let b = node!("B", 10, 10);
+    let mut a = node!("A", 0, 0);
     a.link(&b);
-    assert!(a.hl(0)?.is_connected());
+    a.hl_mut(0)?.weight(5);
+    assert_eq!(a.hl(0)?.weight, Some(5));
+    # Ok(())
+    # }
+    ```
+     */
+    pub fn weight(&mut self, weight: u32) {
+        self.weight = Some(weight);
+    }
+
+    /**
+    Sets the color of the edge, either solid or as a gradient from the start to the end.
+
+
+    ## Examples
+
+    ```
+    # #[macro_use] use pathtracer::*;
+    # use pathtracer::map::theme::Paint;
+    # fn main() -> Result<(), Error> {
+    let b = node!("B", 10, 10);
+    let mut a = node!("A", 0, 0);
+    a.link(&b);
+    let red = image::Rgba([255, 0, 0, 255]);
+    a.hl_mut(0)?.paint(red.into());
+    assert_eq!(a.hl(0)?.paint, Some(Paint::Solid(red)));
+    # Ok(())
+    # }
+    ```
+     */
+    pub fn paint(&mut self, paint: map::theme::Paint) {
+        self.paint = Some(paint);
+    }
+
+    /**
+    Sets the width of the edge in pixels.
+     */
+    pub fn width(&mut self, width: u32) {
+        self.width = Some(width);
+    }
+
+    /**
+    Sets the arrowhead drawn at the end of the edge linked to.
+
+
+    ## Examples
+
+    ```
+    # #[macro_use] use pathtracer::*;
+    # fn main() -> Result<(), Error> {
+    let b = node!("B", 40, 0);
+    let mut a = node!("A", 0, 0);
+    a.link(&b);
+    a.hl_mut(0)?.arrow(Arrow::Filled);
+    let image = Map::new().map(&[a, b]).consume();
+    // The arrow is wider than the edge, in front of B.
+    assert_ne!(image.image().get_pixel(34, 2), image.image().get_pixel(20, 2));
+    # Ok(())
+    # }
+    ```
+     */
+    pub fn arrow(&mut self, arrow: Arrow) {
+        self.arrow = Some(arrow);
+    }
+
+    /**
+    Sets the pattern the edge is stroked with.
+
+
+    ## Examples
+
+    ```
+    # #[macro_use] use pathtracer::*;
+    # fn main() -> Result<(), Error> {
+    let b = node!("B", 40, 0);
+    let mut a = node!("A", 0, 0);
+    a.link(&b);
+    a.hl_mut(0)?.style(EdgeStyle::Bezier(30));
+    a.hl_mut(0)?.stroke(Stroke::Dashed);
+    assert_eq!(a.hl(0)?.stroke, Stroke::Dashed);
+    # Ok(())
+    # }
+    ```
+     */
+    pub fn stroke(&mut self, stroke: Stroke) {
+        self.stroke = stroke;
+    }
+
+    /**
+    Checks if the HL has two endpoint hashes.
+
+
+    ## Examples
+
+    ```
+    # #[macro_use] use pathtracer::*;
+    # fn main() -> Result<(), Error> {
+    let b = cluster!();
+    let mut a = node!();
+    a.link(&b);
+    assert!(a.hl(0)?.is_connected());
     # Ok(())
     # }
     ```
@@ -1029,27 +1683,139 @@ impl HL {
         self.f != 0 && self.t != 0
     }
 
+    /**
+    Returns the corners the edge passes through going from from to to, depending on the EdgeStyle.
+
+    Orthogonal edges are routed around the obstacles.
+     */
+    fn polyline(
+        &self,
+        from: Coordinate,
+        to: Coordinate,
+        obstacles: &[(Coordinate, Coordinate)],
+    ) -> Vec<Coordinate> {
+        match self.style {
+            EdgeStyle::Bezier(curvature) => return tools::bezier_points(from, to, curvature),
+            EdgeStyle::Orthogonal => return map::route::orthogonal(from, to, obstacles),
+            _ => (),
+        }
+        // The plotting algorithms always draw from left to right.
+        let (a, b) = if to.x < from.x {
+            (to, from)
+        } else {
+            (from, to)
+        };
+        let mut line = match self.style {
+            EdgeStyle::Straight if a.x != b.x => vec![a, coordinate!(b.x, a.y), b],
+            EdgeStyle::Ellipse if a.x != b.x => tools::ellipse_points(a, b),
+            _ => vec![a, b],
+        };
+        if a != from {
+            line.reverse();
+        }
+        line
+    }
+
+    /**
+    Returns the corners of the arrowhead at the end of the line, as the two sides and the tip.
+
+    The direction is taken from the last segment of the line, so curves get the tangent they arrive in.
+    The tip stops short of the end by the size of the target, so it is not hidden behind the Node.
+     */
+    fn arrowhead(line: &[Coordinate], width: u32, target: u32) -> Option<[Point; 3]> {
+        let end = *line.last()?;
+        let before = *line.iter().rev().find(|&&c| c != end)?;
+        let d = Point::from(end) - Point::from(before);
+        let length = d.x.hypot(d.y);
+        let d = Point::new(d.x / length, d.y / length);
+        let along = |p: Point, n: f64| Point::new(p.x + d.x * n, p.y + d.y * n);
+
+        let tip = along(Point::from(end), -f64::from(target));
+        let size = f64::from(4 + 3 * width);
+        let base = along(tip, -size);
+        let side = Point::new(-d.y * size / 2.0, d.x * size / 2.0);
+        Some([base + side, tip, base - side])
+    }
+
+    /**
+    Writes the HL as a vector line.
+
+    Will not write the Edge if it is not connected, or if the the HL's from and to connections are the same Node.
+    The HL's own width is used instead of size when set, and arrowheads stop short of the target by its size.
+     */
+    fn draw_svg(&self, svg: &mut map::svg::Svg, offset: Coordinate, size: u32, target: u32) {
+        let (from, to) = self.min_max();
+        if !self.is_connected() || from == to {
+            return;
+        }
+        let size = self.width.unwrap_or(size);
+        let paint = self.paint.unwrap_or_else(|| svg.theme().edge(size));
+        let from = svg.project(Point::from(from + offset));
+        let to = svg.project(Point::from(to + offset));
+        let line = self.polyline(from, to, svg.obstacles());
+        svg.polyline(&line, paint, size, self.stroke);
+        if let (Some(arrow), Some(corners)) = (self.arrow, HL::arrowhead(&line, size, target)) {
+            let corners = corners.map(|c| c.position());
+            let color = paint.at(1.0);
+            match arrow {
+                Arrow::Filled => svg.polygon(&corners, color),
+                Arrow::Open => svg.polyline(&corners, color.into(), size, Stroke::Solid),
+            }
+        }
+    }
+
     /**
     Draws the HL on an Image Wrapper.
 
     Will not draw the Edge if it is not connected, or if the the HL's from and to connections are the same Node.
 
-    Size increases drawing time with a squared factor.
+    Size increases drawing time with a squared factor, the HL's own width is used instead when set.
+    Without a Paint on the HL or in the Theme, each row of pixels is shaded a lighter grey.
+    Arrowheads stop short of the target by its size.
      */
-    fn draw(&self, mut image: IW, mut offset: Coordinate, size: u32) -> IW {
-        let (mut from, mut to) = self.min_max();
+    fn draw(&self, mut image: IW, offset: Coordinate, size: u32, target: u32) -> IW {
+        let (from, to) = self.min_max();
         if !self.is_connected() || from == to {
             return image;
         }
+        let size = self.width.unwrap_or(size);
+        let theme = *image.theme();
+        let paint = self.paint.or(theme.edge);
+        let from = image.project(Point::from(from + offset));
+        let to = image.project(Point::from(to + offset));
+
+        let line = self.polyline(from, to, image.obstacles());
+        let pattern = self.stroke.pattern(size);
+        let arrow = self.arrow.zip(HL::arrowhead(&line, size, target));
+        if image.rasterizer() == map::raster::Rasterizer::AntiAliased {
+            let points = line.into_iter().map(Point::from).collect::<Vec<_>>();
+            let paint = paint.unwrap_or_else(|| theme.edge(size));
+            match pattern {
+                Some(pattern) => {
+                    map::raster::dashed(&mut image, &points, f64::from(size), paint, pattern)
+                }
+                None => map::raster::polyline(&mut image, &points, f64::from(size), paint),
+            }
+            let color = paint.at(1.0);
+            match arrow {
+                Some((Arrow::Filled, corners)) => map::raster::triangle(&mut image, corners, color),
+                Some((Arrow::Open, corners)) => {
+                    map::raster::polyline(&mut image, &corners, f64::from(size), color.into())
+                }
+                None => (),
+            }
+            return image;
+        }
+
         let s = coordinate!(size / 2);
-        offset += s;
-        from += offset;
-        to += offset;
+        let (from, to) = (from + s, to + s);
+        let line = line.into_iter().map(|c| c + s).collect::<Vec<_>>();
 
+        let mut pixels = Vec::new();
         for i in 0..size {
             for j in 0..size {
                 let add = coordinate!(j, i) - s - s;
-                let col = (size - i) as u8 * consts::DEFAULT_SHADE as u8;
+                let paint = paint.unwrap_or_else(|| theme.edge(size - i));
                 let plot = match self.style {
                     EdgeStyle::Direct => {
                         tools::plot_type(from + add, to + add, &tools::plot_bresenham)
@@ -1060,13 +1826,40 @@ impl HL {
                     EdgeStyle::Ellipse => {
                         tools::plot_type(from + add, to + add, &tools::plot_ellipse)
                     }
+                    EdgeStyle::Bezier(_) | EdgeStyle::Orthogonal => line
+                        .windows(2)
+                        .flat_map(|w| {
+                            tools::plot_type(w[0] + add, w[1] + add, &tools::plot_bresenham)
+                        })
+                        .collect(),
                 };
-                let _ = plot
-                    .iter()
-                    .map(|c| image.put(c, image::Rgb([col, col, col])))
-                    .collect::<Vec<_>>();
+                let (on, off) = pattern.unwrap_or((1, 0));
+                pixels.extend(
+                    plot.into_iter()
+                        .enumerate()
+                        .filter(|(n, _)| *n as u32 % (on + off) < on)
+                        .map(|(_, c)| {
+                            let color =
+                                paint.along(Point::from(from), Point::from(to), Point::from(c));
+                            (c, color)
+                        }),
+                );
             }
         }
+        if let Some((arrow, corners)) = arrow {
+            let [left, tip, right] = corners.map(|c| c.position());
+            let plot = match arrow {
+                Arrow::Filled => tools::plot_triangle(left, tip, right),
+                Arrow::Open => {
+                    let mut plot = tools::plot_type(left, tip, &tools::plot_bresenham);
+                    plot.extend(tools::plot_type(tip, right, &tools::plot_bresenham));
+                    plot
+                }
+            };
+            let color = paint.unwrap_or_else(|| theme.edge(size)).at(1.0);
+            pixels.extend(plot.into_iter().map(|c| (c, color)));
+        }
+        image.paint(pixels);
         image
     }
 }
@@ -1077,10 +1870,49 @@ impl Group {
 
     name is converted internally as a hash.
      */
-    pub fn new(name: &str, coordinates: Coordinate) -> Self {
+    pub fn new<P: Into<Point>>(name: &str, coordinates: P) -> Self {
         Group {
             settings: Node::new(name, coordinates),
             nodes: Vec::new(),
+            rng: None,
+        }
+    }
+
+    /**
+    Seeds the positions of the Nodes added to the Group.
+
+    Without a seed, Nodes are placed using the thread's random number generator. With one, the same
+    seed and sequence of additions always place the Nodes in the same positions.
+
+
+    ## Examples
+
+    ```
+    # use pathtracer::*;
+    let mut a = Group::new("", Coordinate::new(0, 0));
+    a.seed(42);
+    let mut b = a.clone();
+    a.add(20);
+    b.add(20);
+    let positions = |g: &Group| g.nodes.iter().map(|n| n.geo).collect::<Vec<_>>();
+    assert_eq!(positions(&a), positions(&b));
+    ```
+     */
+    pub fn seed(&mut self, seed: u64) {
+        self.rng = Some(StdRng::seed_from_u64(seed));
+    }
+
+    /**
+    Calls the closure with the Group's seeded random number generator, or the thread's if it has none.
+     */
+    fn with_rng<T>(&mut self, f: impl FnOnce(&mut Group, &mut dyn RngCore) -> T) -> T {
+        match self.rng.take() {
+            Some(mut rng) => {
+                let result = f(self, &mut rng);
+                self.rng = Some(rng);
+                result
+            }
+            None => f(self, &mut rand::thread_rng()),
         }
     }
 
@@ -1100,7 +1932,7 @@ impl Group {
     ```
      */
     pub fn new_node(&mut self) {
-        group::add_node(self, None, None, None);
+        self.with_rng(|group, rng| group::add_node_with(rng, group, None, None, None));
     }
 
     /**
@@ -1179,12 +2011,14 @@ impl Group {
     ```
      */
     pub fn add(&mut self, nr: u32) {
-        for _ in 0..nr {
-            let co = coordinate::gen_within_radius(self.position(), self.size());
-            let mut node = node!(co);
-            node.color = self.gen_color(co);
-            self.push(node);
-        }
+        self.with_rng(|group, rng| {
+            for _ in 0..nr {
+                let co = coordinate::gen_within_radius_with(rng, group.position(), group.size());
+                let mut node = node!(co);
+                node.color = group.gen_color(co);
+                group.push(node);
+            }
+        });
     }
 
     /**
@@ -1211,7 +2045,7 @@ impl Group {
     # fn main() {
     # let mut group = cluster!();
     # group.add(10);
-    group.each(&|node: &mut Node| node.geo = coordinate!());
+    group.each(&|node: &mut Node| node.geo = Point::default());
     # }
     ```
      */
@@ -1272,8 +2106,22 @@ impl Group {
     /**
        Sets the color of the Group.
     */
-    pub fn color(&mut self, rgb: image::Rgb<u8>) {
-        self.settings.color = rgb;
+    pub fn color(&mut self, rgba: image::Rgba<u8>) {
+        self.settings.color = Some(rgba);
+    }
+
+    /**
+    Sets the label drawn next to the Group.
+     */
+    pub fn label(&mut self, label: &str) {
+        self.settings.label = Some(label.to_string());
+    }
+
+    /**
+    Sets the shape the nodes of the Group are drawn as, unless they have a shape of their own.
+     */
+    pub fn shape(&mut self, shape: Shape) {
+        self.settings.shape = Some(shape);
     }
 
     /**
@@ -1311,12 +2159,12 @@ impl Group {
     # fn main() {
     let mut group = cluster!();
     group.new_node_min_max(50, 60);
-    assert!(group.nodes()[0].geo.lt(61));
+    assert!(group.nodes()[0].position().lt(61));
     # }
     ```
      */
     pub fn new_node_min_max(&mut self, min: u32, max: u32) {
-        group::add_node(self, None, Some(min), Some(max));
+        self.with_rng(|group, rng| group::add_node_with(rng, group, None, Some(min), Some(max)));
     }
 
     /**
@@ -1336,7 +2184,7 @@ impl Group {
     # }
     ```
      */
-    pub fn new_simple(x: i16, y: i16) -> Self {
+    pub fn new_simple(x: i32, y: i32) -> Self {
         Group::new(&(x + y).to_string(), Coordinate::new(x, y))
     }
 
@@ -1357,7 +2205,7 @@ impl Group {
     ```
      */
     pub fn push(&mut self, mut node: Node) {
-        node.geo -= self.position();
+        node.geo -= self.settings.geo;
         self.nodes.push(node);
     }
 
@@ -1382,9 +2230,10 @@ impl Group {
     }
 
     /**
-    Generate a image::Rgb based on the color of the Group and the distance from center.
+    Generate a image::Rgba based on the color of the Group and the distance from center.
 
     This is useful to make nodes places in groups, but outside it's radius or close to it's radius appear as darker.
+    Returns None if the Group has no color, leaving its Nodes to be drawn in the Theme's node color.
 
 
     ## Examples
@@ -1394,19 +2243,21 @@ impl Group {
     # fn main() {
     let mut cluster = cluster!();
     cluster.radius(10);
-    cluster.color(image::Rgb {0: [100, 100, 100]});
-    let rgb = cluster.gen_color(coordinate!(10, 10));
+    cluster.color(image::Rgba {0: [100, 100, 100, 255]});
+    let rgb = cluster.gen_color(coordinate!(10, 10)).unwrap();
     assert!(rgb.0[0] < 50);
     # }
     ```
      */
-    pub fn gen_color(&self, coordinates: Coordinate) -> image::Rgb<u8> {
-        tools::range_color(
-            self.dynamic_radius() as i16,
-            self.settings.color,
-            self.settings.geo,
-            coordinates,
-        )
+    pub fn gen_color(&self, coordinates: Coordinate) -> Option<image::Rgba<u8>> {
+        self.settings.color.map(|color| {
+            tools::range_color(
+                self.dynamic_radius() as i32,
+                color,
+                self.position(),
+                coordinates,
+            )
+        })
     }
 
     /**
@@ -1423,7 +2274,7 @@ impl Group {
     assert_eq!(groups.len(), 3);
     ```
      */
-    pub fn from_list(list: &[(i16, i16)]) -> Vec<Self> {
+    pub fn from_list(list: &[(i32, i32)]) -> Vec<Self> {
         coordinate::from_list(list, &|c, i| {
             Group::new(&std::char::from_u32(65 + i as u32).unwrap().to_string(), c)
         })
@@ -1447,15 +2298,88 @@ impl Group {
     }
 }
 
-impl<T: Draw + Hash + std::marker::Copy> Network<T> {
-    pub fn new(mut elements: Vec<T>) -> Self {
-        let mut hash_map: [Option<T>; consts::NETWORK_REM] = [None; consts::NETWORK_REM];
-        while !elements.is_empty() {
-            let e = elements.remove(0);
-            hash_map[e.hash() as usize % consts::NETWORK_REM] = Some(e);
+impl<T: Draw + Hash> Network<T> {
+    /**
+    Constructs a Network from a list of elements, keyed by their hashes.
+
+    Elements sharing the same hash replace each other, the last one is kept.
+     */
+    pub fn new(elements: Vec<T>) -> Self {
+        let mut network = Network {
+            hash_map: HashMap::with_capacity(elements.len()),
+        };
+        for e in elements {
+            network.insert(e);
         }
+        network
+    }
+
+    /**
+    Inserts an element in to the network.
+
+    Returns the element previously stored with the same hash, if any.
+
 
-        Network { hash_map }
+    ## Examples
+
+    ```
+    # use pathtracer::*;
+    let mut network = Network::new(Node::from_list(&[(0, 0)]));
+    let previous = network.insert(Node::new("B", Coordinate::new(10, 10)));
+    assert!(previous.is_none());
+    assert_eq!(network.len(), 2);
+    ```
+     */
+    pub fn insert(&mut self, element: T) -> Option<T> {
+        self.hash_map.insert(element.hash(), element)
+    }
+
+    /**
+    Removes and returns the element with the given hash.
+
+
+    ## Examples
+
+    ```
+    # use pathtracer::*;
+    let nodes = Node::from_list(&[(0, 0), (10, 10)]);
+    let hash = nodes[0].hash();
+    let mut network = Network::new(nodes);
+    assert!(network.remove(hash).is_some());
+    assert!(network.remove(hash).is_none());
+    assert_eq!(network.len(), 1);
+    ```
+     */
+    pub fn remove(&mut self, hash: u64) -> Option<T> {
+        self.hash_map.remove(&hash)
+    }
+
+    /**
+    Returns a reference to the element with the given hash.
+     */
+    pub fn lookup(&self, hash: u64) -> Option<&T> {
+        self.hash_map.get(&hash)
+    }
+
+    /**
+    Returns true if an element with the given hash exists in the network.
+     */
+    pub fn contains(&self, hash: u64) -> bool {
+        self.hash_map.contains_key(&hash)
+    }
+
+    /**
+    Returns the number of elements in the network.
+     */
+    pub fn len(&self) -> usize {
+        self.hash_map.len()
+    }
+
+    /**
+    Returns true if the network holds no elements.
+     */
+    pub fn is_empty(&self) -> bool {
+        self.hash_map.is_empty()
     }
 }
 
@@ -1466,39 +2390,367 @@ impl Map {
     Creates a new map, no min_max are intially required and are generated automatically when calling Map::map.
      */
     pub fn new() -> Self {
+        Map::default()
+    }
+
+    /**
+    Creates a new map which writes a SVG document instead of an image.
+
+
+    ## Examples
+
+    ```
+    # use pathtracer::*;
+    # use std::path::Path;
+    # fn main() -> Result<(), Error> {
+    let nodes = Node::linked_list(Node::from_list(&[(0, 0), (10, 10)]));
+    let map = Map::new_svg().map(&nodes);
+    assert!(map.svg().unwrap().to_string().contains("<line"));
+    map.save(Path::new("/tmp/example.svg"))?;
+    # Ok(())
+    # }
+    ```
+     */
+    pub fn new_svg() -> Self {
         Map {
-            image: None,
-            add: coordinate!(),
+            vector: true,
+            ..Map::new()
         }
     }
 
+    /**
+    Sets a fixed width and height for the canvas, instead of sizing it after the first elements mapped.
+
+    World position 0,0 is drawn at the top left corner, inside the padding. Elements outside the canvas are clipped.
+
+
+    ## Examples
+
+    ```
+    # use pathtracer::*;
+    let nodes = Node::from_list(&[(0, 0), (1000, 1000)]);
+    let image = Map::new().size(200, 100).map(&nodes).consume();
+    assert_eq!(image.dimensions(), Coordinate::new(200, 100));
+    ```
+     */
+    pub fn size(mut self, width: u32, height: u32) -> Self {
+        self.size = Some((width, height));
+        self
+    }
+
+    /**
+    Draws the world position center in the middle of the canvas, scaled by zoom.
+
+    Only has an effect when a size is set, and is ignored when fitting.
+    A zoom of zero or less is raised to map::MIN_ZOOM.
+
+
+    ## Examples
+
+    ```
+    # use pathtracer::*;
+    let mut node = Node::new("A", Coordinate::new(30, 30));
+    node.color = Some(image::Rgba([255, 0, 0, 255]));
+    let image = Map::new()
+        .size(100, 100)
+        .viewport(Point::new(25.0, 25.0), 2.0)
+        .map(&[node])
+        .consume();
+    // The world origin is at the top left corner, with twice the distance to the node.
+    assert_eq!(image.image().get_pixel(61, 61).0, [255, 0, 0, 255]);
+    ```
+     */
+    pub fn viewport(mut self, center: Point, zoom: f64) -> Self {
+        self.view = Some((center, zoom));
+        self
+    }
+
+    /**
+    Sets the number of pixels left empty around the elements.
+     */
+    pub fn padding(mut self, padding: u32) -> Self {
+        self.padding = padding;
+        self
+    }
+
+    /**
+    Keeps every mapped slice as a layer and fits the canvas to the bounds of all of them.
+
+    Each call to map draws all layers again, so elements mapped later are never clipped.
+    With a size set, the layers are zoomed to fill the canvas.
+    Layers keep the Snapshot of each element. Elements without one are drawn on the canvas as it is when they are mapped,
+    with a warning, so they do not move the bounds and are cleared when a later map draws the layers again.
+
+
+    ## Examples
+
+    ```
+    # use pathtracer::*;
+    let a = Node::from_list(&[(0, 0), (10, 10)]);
+    let b = Node::from_list(&[(-500, 300)]);
+    let image = Map::new().fit().padding(5).map(&a).map(&b).consume();
+    assert_eq!(image.dimensions(), Coordinate::new(528, 318));
+    ```
+     */
+    pub fn fit(mut self) -> Self {
+        self.fit = true;
+        self
+    }
+
+    /**
+    Sets how the elements are turned in to pixels, must be set before anything is mapped.
+
+    SVG documents are left to the viewer to rasterize.
+
+
+    ## Examples
+
+    ```
+    # use pathtracer::{map::raster::Rasterizer, *};
+    let nodes = Node::linked_list(Node::from_list(&[(0, 0), (100, 30)]));
+    let image = Map::new()
+        .rasterizer(Rasterizer::AntiAliased)
+        .map_shape(&nodes, &Shape::Circle)
+        .consume();
+    assert_eq!(image.rasterizer(), Rasterizer::AntiAliased);
+    ```
+     */
+    pub fn rasterizer(mut self, rasterizer: map::raster::Rasterizer) -> Self {
+        self.rasterizer = rasterizer;
+        self
+    }
+
+    /**
+    Sets how colors are combined with what is already drawn, must be set before anything is mapped.
+
+    With Composite::Add, translucent nodes brighten where they overlap, so dense regions render as heat.
+
+
+    ## Examples
+
+    ```
+    # use pathtracer::{map::composite::Composite, *};
+    let mut nodes = Node::from_list(&[(0, 0), (0, 0), (0, 0)]);
+    for node in nodes.iter_mut() {
+        node.color = Some(image::Rgba([255, 0, 0, 80]));
+    }
+    let over = Map::new().map(&nodes).consume();
+    let heat = Map::new().composite(Composite::Add).map(&nodes).consume();
+    let brightest = |image: &IW| image.image().pixels().map(|p| p.0[0]).max();
+    assert!(brightest(&heat) > brightest(&over));
+    ```
+     */
+    pub fn composite(mut self, composite: map::composite::Composite) -> Self {
+        self.composite = composite;
+        self
+    }
+
+    /**
+    Sets the colors and sizes used for drawing, must be set before anything is mapped.
+
+
+    ## Examples
+
+    ```
+    # use pathtracer::{map::theme::*, *};
+    let nodes = Node::linked_list(Node::from_list(&[(0, 0), (100, 0)]));
+    let theme = Theme {
+        background: image::Rgba([250, 250, 240, 255]),
+        node: image::Rgba([0, 60, 120, 255]),
+        edge: Some(Paint::Gradient(
+            image::Rgba([0, 60, 120, 255]),
+            image::Rgba([240, 120, 0, 255]),
+        )),
+        ..Theme::light()
+    };
+    let image = Map::new().theme(theme).map(&nodes).consume();
+    assert_eq!(image.image().get_pixel(0, 0).0, [250, 250, 240, 255]);
+    ```
+     */
+    pub fn theme(mut self, theme: map::theme::Theme) -> Self {
+        self.theme = theme;
+        self
+    }
+
+    /**
+    Sets which side of Nodes and Groups their labels are preferably drawn on, must be set before anything is mapped.
+
+    A label is moved to another side when it would overlap a label drawn before it, and left out if every side does.
+
+
+    ## Examples
+
+    ```
+    # use pathtracer::{map::label::Placement, *};
+    let mut nodes = Node::from_list(&[(0, 0), (60, 0)]);
+    nodes[0].label("Start");
+    nodes[1].label("Goal");
+    let image = Map::new()
+        .size(100, 40)
+        .padding(15)
+        .labels(Placement::Below)
+        .map(&nodes)
+        .consume();
+    let white = image.image().pixels().filter(|p| p.0 == [255, 255, 255, 255]).count();
+    assert!(white > 0);
+    ```
+     */
+    pub fn labels(mut self, placement: map::label::Placement) -> Self {
+        self.placement = placement;
+        self
+    }
+
+    /**
+    Returns the SVG document, if the map was created using new_svg and something has been mapped.
+     */
+    pub fn svg(&self) -> Option<&map::svg::Svg> {
+        self.svg.as_ref()
+    }
+
     /**
     Saves the image to disk at the given Path.
 
+    Maps created with new_svg are saved as a SVG document regardless of the extension.
+    Formats without an alpha channel, such as JPEG, are saved without it.
+
 
     ## Examples
 
     ```
     # use pathtracer::*;
     # use std::path::Path;
-    # fn main() -> std::io::Result<()> {
+    # fn main() -> Result<(), Error> {
     let nodes = Node::from_list(&[(0, 0), (10, 10)]);
     Map::new()
     .map(&nodes)
-    .save(Path::new("/tmp/example.png")).unwrap();
+    .save(Path::new("/tmp/example.png"))?;
     # Ok(())
     # }
     ```
      */
-    pub fn save(self, path: &std::path::Path) -> Result<(), image::ImageError> {
-        self.image.unwrap().image().save(path)
+    pub fn save(self, path: &std::path::Path) -> Result<(), Error> {
+        if let Some(svg) = self.svg {
+            return Ok(std::fs::write(path, svg.to_string())?);
+        }
+        let image = self.image.ok_or(Error::NotMapped)?;
+        if image::ImageFormat::from_path(path)? == image::ImageFormat::Jpeg {
+            return Ok(image::DynamicImage::ImageRgba8(image.img)
+                .to_rgb8()
+                .save(path)?);
+        }
+        Ok(image.image().save(path)?)
+    }
+
+    /**
+    Encodes the image in the format, writing it to the writer.
+
+    Maps created with new_svg are written as a SVG document, and only with Format::Svg.
+
+
+    ## Errors
+
+    Returns NotMapped if nothing has been mapped, InvalidArgument if the format does not match the kind of Map,
+    or an ImageError if encoding or writing fails.
+
+
+    ## Examples
+
+    ```
+    # use pathtracer::{map::format::Format, *};
+    let map = Map::new().map(&Node::from_list(&[(0, 0), (10, 10)]));
+    let mut jpeg = Vec::new();
+    map.write(&mut jpeg, Format::Jpeg(90)).unwrap();
+    assert_eq!(&jpeg[..2], &[0xFF, 0xD8]);
+    assert!(Map::new().write(&mut jpeg, Format::Png).is_err());
+    assert!(map.write(&mut jpeg, Format::Svg).is_err());
+
+    let svg = Map::new_svg().map(&Node::from_list(&[(0, 0), (10, 10)]));
+    let mut document = Vec::new();
+    svg.write(&mut document, Format::Svg).unwrap();
+    assert!(document.starts_with(b"<svg"));
+    assert!(svg.write(&mut document, Format::Png).is_err());
+    ```
+     */
+    pub fn write<W: std::io::Write>(
+        &self,
+        mut writer: W,
+        format: map::format::Format,
+    ) -> Result<(), Error> {
+        if let Some(svg) = &self.svg {
+            if format != map::format::Format::Svg {
+                return Err(Error::InvalidArgument(format!(
+                    "SVG maps can not be written as {}",
+                    format.extension()
+                )));
+            }
+            return Ok(writer.write_all(svg.to_string().as_bytes())?);
+        }
+        let image = self.image.as_ref().ok_or(Error::NotMapped)?;
+        format.encode(image.image(), writer)
+    }
+
+    /**
+    Encodes the image in the format, returning the bytes.
+
+
+    ## Errors
+
+    Returns NotMapped if nothing has been mapped, InvalidArgument if the format does not match the kind of Map,
+    or an ImageError if encoding fails.
+
+
+    ## Examples
+
+    ```
+    # use pathtracer::{map::format::Format, *};
+    let bytes = Map::new()
+        .map(&Node::from_list(&[(0, 0), (10, 10)]))
+        .encode(Format::Png)
+        .unwrap();
+    let image = image::load_from_memory(&bytes).unwrap();
+    assert!(image.width() > 10);
+    ```
+     */
+    pub fn encode(&self, format: map::format::Format) -> Result<Vec<u8>, Error> {
+        let mut bytes = Vec::new();
+        self.write(&mut bytes, format)?;
+        Ok(bytes)
+    }
+
+    /**
+    Returns the ImageWrapper, if something has been mapped on a raster Map.
+     */
+    pub fn image(&self) -> Option<&IW> {
+        self.image.as_ref()
+    }
+
+    /**
+    Consumes the Map and returns the image buffer, or None if nothing has been mapped.
+
+
+    ## Examples
+
+    ```
+    # use pathtracer::*;
+    assert!(Map::new().into_image().is_none());
+    let image = Map::new().size(20, 10).map::<Node>(&[]).into_image().unwrap();
+    assert_eq!(image.dimensions(), (20, 10));
+    ```
+     */
+    pub fn into_image(self) -> Option<image::ImageBuffer<image::Rgba<u8>, Vec<u8>>> {
+        self.image.map(|image| image.img)
     }
 
     /**
     Consumes the Map and returns the ImageWrapper.
+
+
+    ## Panics
+
+    Panics if nothing has been mapped. Use into_image to get the buffer without panicking.
      */
     pub fn consume(self) -> IW {
-        self.image.unwrap()
+        self.image.expect("nothing has been mapped")
     }
 
     /**
@@ -1547,9 +2799,9 @@ impl Map {
     Maps the elements without stabalizing the positions on the canvas.
      */
     pub fn map_absolute<T: Draw + Location + Hash + MinMax>(mut self, element: &[T]) -> Self {
-        if self.image.is_none() {
-            let (image, _) = map::gen_map(element);
-            self.image = Some(IW { img: image });
+        if self.image.is_none() && self.svg.is_none() && self.size.is_none() && !self.fit {
+            let (size, _) = map::bounds(element);
+            self.canvas(size.x as u32, size.y as u32, map::Viewport::default());
         }
         self.map(element)
     }
@@ -1563,21 +2815,156 @@ impl Map {
         filter: &dyn Fn(&T) -> bool,
         shape: &Shape,
     ) -> Self {
-        if self.image.is_none() {
-            let (image, add) = map::gen_map(element);
-            self.image = Some(IW { img: image });
-            self.add = add;
+        if self.fit {
+            let (mut elements, mut missing) = (Vec::new(), false);
+            for x in element.iter().filter(|x| filter(x)) {
+                match x.snapshot() {
+                    Some(snapshot) => elements.push(snapshot),
+                    None => missing = true,
+                }
+            }
+            self.layers.push((elements, shape.clone()));
+            self = self.redraw();
+            if missing {
+                warn!("elements without a Snapshot are drawn without fitting the canvas to them");
+                let unfitted = |x: &T| filter(x) && x.snapshot().is_none();
+                return self.draw_elements(element, &unfitted, shape);
+            }
+            return self;
+        }
+        self.draw_elements(element, filter, shape)
+    }
+
+    /**
+    Draws the elements, creating the canvas from their bounds if there is none yet.
+     */
+    fn draw_elements<T: Draw + Location + Hash + MinMax>(
+        mut self,
+        element: &[T],
+        filter: &dyn Fn(&T) -> bool,
+        shape: &Shape,
+    ) -> Self {
+        if self.image.is_none() && self.svg.is_none() {
+            let p = self.padding as i32;
+            match self.size {
+                Some((w, h)) => {
+                    let viewport = match self.view {
+                        Some((center, zoom)) => map::Viewport::centered(center, zoom, w, h),
+                        None => map::Viewport::new(Point::from(coordinate!(-p)), 1.0),
+                    };
+                    self.canvas(w, h, viewport);
+                }
+                None => {
+                    let (size, add) = map::bounds(element);
+                    let size = size + coordinate!(2 * p);
+                    self.canvas(size.x as u32, size.y as u32, map::Viewport::default());
+                    self.add = add + coordinate!(p);
+                }
+            }
         }
 
         let add = self.add;
-        self.image = Some(
-            element
-                .iter()
-                .filter(|x| filter(x))
-                .fold(self.image.unwrap(), |img, x| x.draw(img, add, shape)),
-        );
+        if let Some(svg) = self.svg.as_mut() {
+            for x in element.iter().filter(|x| filter(x)) {
+                let (min, max) = obstacle(x, svg.project(Point::from(x.position() + add)));
+                svg.avoid(min, max);
+                svg.sized(x.hash(), x.size());
+            }
+            for x in element.iter().filter(|x| filter(x)) {
+                x.draw_svg(svg, add, shape);
+            }
+        }
+        if let Some(mut image) = self.image.take() {
+            for x in element.iter().filter(|x| filter(x)) {
+                let (min, max) = obstacle(x, image.project(Point::from(x.position() + add)));
+                image.avoid(min, max);
+                image.sized(x.hash(), x.size());
+            }
+            self.image = Some(
+                element
+                    .iter()
+                    .filter(|x| filter(x))
+                    .fold(image, |img, x| x.draw(img, add, shape)),
+            );
+        }
         self
     }
+
+    /**
+    Creates an empty image or SVG document.
+     */
+    fn canvas(&mut self, width: u32, height: u32, viewport: map::Viewport) {
+        if self.vector {
+            self.svg = Some(
+                map::svg::Svg::with_viewport(width, height, viewport)
+                    .with_theme(self.theme)
+                    .with_labels(self.placement),
+            );
+        } else {
+            self.image = Some(IW {
+                img: map::gen_canvas(width, height, self.theme.background),
+                viewport,
+                rasterizer: self.rasterizer,
+                composite: self.composite,
+                theme: self.theme,
+                labels: map::label::Labels::new(self.placement),
+                obstacles: Vec::new(),
+                sizes: HashMap::new(),
+            });
+        }
+    }
+
+    /**
+    Draws every layer again, on a canvas fitting the bounds of them all.
+     */
+    fn redraw(mut self) -> Self {
+        let (min, max) = self
+            .layers
+            .iter()
+            .filter_map(map::layer_min_max)
+            .reduce(|a, b| {
+                (
+                    coordinate!(std::cmp::min(a.0.x, b.0.x), std::cmp::min(a.0.y, b.0.y)),
+                    coordinate!(std::cmp::max(a.1.x, b.1.x), std::cmp::max(a.1.y, b.1.y)),
+                )
+            })
+            .unwrap_or_default();
+        let p = self.padding;
+        let extent = max - min;
+
+        let (w, h, viewport) = match self.size {
+            Some((w, h)) => {
+                let zoom = f64::min(
+                    f64::from(w.saturating_sub(2 * p).max(1)) / f64::from(extent.x.max(1)),
+                    f64::from(h.saturating_sub(2 * p).max(1)) / f64::from(extent.y.max(1)),
+                );
+                let center = Point::new(
+                    f64::from(min.x + max.x) / 2.0,
+                    f64::from(min.y + max.y) / 2.0,
+                );
+                (w, h, map::Viewport::centered(center, zoom, w, h))
+            }
+            None => {
+                let origin = Point::from(min - coordinate!(p));
+                (
+                    extent.x as u32 + 2 * p,
+                    extent.y as u32 + 2 * p,
+                    map::Viewport::new(origin, 1.0),
+                )
+            }
+        };
+
+        self.image = None;
+        self.svg = None;
+        self.add = coordinate!();
+        self.canvas(w, h, viewport);
+        let layers = std::mem::take(&mut self.layers);
+        let mut map = layers.iter().fold(self, |map, (elements, shape)| {
+            map.draw_elements(elements, &|_| true, shape)
+        });
+        map.layers = layers;
+        map
+    }
 }
 
 impl Network<Node> {
@@ -1595,8 +2982,8 @@ impl Network<Node> {
     assert_eq!(path.len(), 4);
     ```
      */
-    pub fn path(&self, a: &str, b: &str) -> std::io::Result<Vec<Node>> {
-        let mut path = map::network::path(self, b, a, &map::network::path_shortest_leg)?;
+    pub fn path(&self, a: &str, b: &str) -> Result<Vec<Node>, Error> {
+        let mut path = map::network::path(self, b, a, &map::network::path_dijkstra)?;
         path.reverse();
         Ok(path)
     }
@@ -1604,8 +2991,48 @@ impl Network<Node> {
     /**
     Mimics path behaviour but works in reverse, Meaning stepping back in the links.
      */
-    pub fn path_rev(&self, a: &str, b: &str) -> std::io::Result<Vec<Node>> {
-        map::network::path(self, a, b, &map::network::path_shortest_leg)
+    pub fn path_rev(&self, a: &str, b: &str) -> Result<Vec<Node>, Error> {
+        map::network::path(self, a, b, &map::network::path_dijkstra)
+    }
+
+    /**
+    Paints the links between each pair of Nodes following each other in the path, and sets their width.
+
+    Links in either direction are painted, so paths from both path and path_rev can be highlighted.
+
+
+    ## Examples
+
+    Draws the path in red over the rest of the network.
+
+    ```
+    # use pathtracer::*;
+    # fn main() -> Result<(), Error> {
+    let nodes = Node::linked_list(Node::from_list(&[(0, 0), (30, 10), (60, 0), (90, 10)]));
+    let mut network = Network::new(nodes);
+    let path = network.path("B", "D")?;
+    network.highlight(&path, image::Rgba([255, 0, 0, 255]).into(), 3);
+    // Each Node links to the one before it.
+    assert_eq!(network.get("B").unwrap().hl(0)?.paint, None);
+    assert_eq!(network.get("C").unwrap().hl(0)?.width, Some(3));
+
+    let nodes = network.hash_map.values().cloned().collect::<Vec<_>>();
+    Map::new().map(&nodes).save(std::path::Path::new("/tmp/highlight.png"))?;
+    # Ok(())
+    # }
+    ```
+     */
+    pub fn highlight(&mut self, path: &[Node], paint: map::theme::Paint, width: u32) {
+        for pair in path.windows(2) {
+            for (f, t) in [(pair[0].hash, pair[1].hash), (pair[1].hash, pair[0].hash)].iter() {
+                if let Some(node) = self.hash_map.get_mut(f) {
+                    for link in node.links.iter_mut().filter(|l| l.t == *t) {
+                        link.paint = Some(paint);
+                        link.width = Some(width);
+                    }
+                }
+            }
+        }
     }
 
     /**
@@ -1625,4 +3052,23 @@ impl Network<Node> {
     pub fn get(&self, element: &str) -> Option<Node> {
         map::network::get(self, element)
     }
+
+    /**
+    Returns the network as a Graphviz DOT graph, with the nodes ordered by their hashes.
+
+
+    ## Examples
+
+    ```
+    # use pathtracer::*;
+    let nodes = Node::linked_list(Node::from_list(&[(0, 0), (10, 10)]));
+    let dot = Network::new(nodes).to_dot();
+    assert_eq!(dot.matches(" -> ").count(), 1);
+    ```
+     */
+    pub fn to_dot(&self) -> String {
+        let mut nodes = self.hash_map.values().cloned().collect::<Vec<_>>();
+        nodes.sort_by_key(|n| n.hash);
+        data::dot::to_string(&nodes)
+    }
 }
@@ -23,7 +23,7 @@ These provide a less-strict typed struct versions which lightens the syntax.
 
   Type conversion is performed to make invocation easier.
 
-  If you are not sure if the value you pass can be converted to a i16, do not use this macro as values could overflow.
+  If you are not sure if the value you pass can be converted to a i32, do not use this macro as values could overflow.
 
   ```
   # #![macro_use] use pathtracer::*;
@@ -45,14 +45,14 @@ macro_rules! coordinate {
     };
 
     ($x:expr, $y:expr) => {
-        Coordinate::new($x as i16, $y as i16)
+        Coordinate::new($x as i32, $y as i32)
     };
 }
 
 /**
   initalize Nodes using a range of parameters.
 
-  Since the macro calls coordinate! it allows for type conversion, be aware that if your values can not be cast to i16. it is better to avoid these macro invocations.
+  Since the macro calls coordinate! it allows for type conversion, be aware that if your values can not be cast to i32. it is better to avoid these macro invocations.
 
   ## Examples
 
@@ -92,7 +92,7 @@ macro_rules! node {
 /**
   initalize Groups using a range of parameters.
 
-  Since the macro calls coordinate! it allows for type conversion, be aware that if your values can not be cast to i16. it is better to avoid these macro invocations.
+  Since the macro calls coordinate! it allows for type conversion, be aware that if your values can not be cast to i32. it is better to avoid these macro invocations.
 
 
   ## Examples
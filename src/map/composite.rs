@@ -0,0 +1,127 @@
+/*!
+Alpha compositing.
+
+Decides how a color is combined with the pixel it is drawn on, using its alpha channel.
+Colors are combined premultiplied by their alpha, following the W3C compositing specification.
+ */
+
+use image::Rgba;
+
+/**
+How a drawn color is combined with what is already on the canvas.
+
+
+## Over
+
+The color is painted on top, covering the pixel by its opacity. Opaque colors replace the pixel.
+
+
+## Add
+
+The colors are summed, so overlapping elements get brighter. Dense regions render as heat.
+
+
+## Multiply
+
+The colors are multiplied, so overlapping elements get darker.
+ */
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Composite {
+    #[default]
+    Over,
+    Add,
+    Multiply,
+}
+
+impl Composite {
+    /**
+    Returns the source color drawn on the destination color.
+
+
+    ## Examples
+
+    ```
+    # use pathtracer::map::composite::Composite;
+    let dst = image::Rgba([100, 0, 0, 255]);
+    let src = image::Rgba([100, 200, 0, 128]);
+    assert_eq!(Composite::Over.apply(dst, src).0, [100, 100, 0, 255]);
+    assert_eq!(Composite::Add.apply(dst, src).0, [150, 100, 0, 255]);
+    assert_eq!(Composite::Multiply.apply(dst, src).0, [69, 0, 0, 255]);
+    ```
+     */
+    pub fn apply(self, dst: Rgba<u8>, src: Rgba<u8>) -> Rgba<u8> {
+        let unit = |c: u8| f64::from(c) / 255.0;
+        let (sa, da) = (unit(src[3]), unit(dst[3]));
+
+        let alpha = match self {
+            Composite::Add => (sa + da).min(1.0),
+            Composite::Over | Composite::Multiply => sa + da * (1.0 - sa),
+        };
+
+        let mut out = [0; 4];
+        for i in 0..3 {
+            let (s, d) = (unit(src[i]), unit(dst[i]));
+            let premultiplied = match self {
+                Composite::Over => s * sa + d * da * (1.0 - sa),
+                Composite::Add => (s * sa + d * da).min(1.0),
+                Composite::Multiply => s * sa * (1.0 - da) + d * da * (1.0 - sa) + s * sa * d * da,
+            };
+            out[i] = if alpha > 0.0 {
+                (premultiplied / alpha * 255.0).round().min(255.0) as u8
+            } else {
+                0
+            };
+        }
+        out[3] = (alpha * 255.0).round() as u8;
+        Rgba(out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MODES: [Composite; 3] = [Composite::Over, Composite::Add, Composite::Multiply];
+
+    #[test]
+    fn transparent_source_keeps_pixel() {
+        let dst = Rgba([10, 20, 30, 255]);
+        for mode in MODES.iter() {
+            assert_eq!(mode.apply(dst, Rgba([255, 255, 255, 0])), dst, "{:?}", mode);
+        }
+    }
+
+    #[test]
+    fn transparent_destination_takes_source() {
+        let src = Rgba([10, 20, 30, 200]);
+        for mode in MODES.iter() {
+            assert_eq!(mode.apply(Rgba([0, 0, 0, 0]), src), src, "{:?}", mode);
+        }
+    }
+
+    #[test]
+    fn opaque_over_replaces() {
+        let src = Rgba([1, 2, 3, 255]);
+        assert_eq!(Composite::Over.apply(Rgba([200, 200, 200, 255]), src), src);
+    }
+
+    #[test]
+    fn add_accumulates_heat() {
+        let heat = Rgba([255, 0, 0, 64]);
+        let mut pixel = Rgba([0, 0, 0, 255]);
+        let mut previous = 0;
+        for _ in 0..4 {
+            pixel = Composite::Add.apply(pixel, heat);
+            assert!(pixel[0] > previous);
+            previous = pixel[0];
+        }
+        assert_eq!(pixel.0, [255, 0, 0, 255]);
+    }
+
+    #[test]
+    fn multiply_darkens() {
+        let pixel =
+            Composite::Multiply.apply(Rgba([200, 100, 50, 255]), Rgba([128, 128, 128, 255]));
+        assert_eq!(pixel.0, [100, 50, 25, 255]);
+    }
+}
@@ -0,0 +1,225 @@
+/*!
+Image formats a Map can be encoded in.
+
+Images are encoded to any Write, such as a file or a Vec, so they can be sent without being saved to disk first.
+ */
+
+use super::super::*;
+use image::{
+    codecs::{bmp::BmpEncoder, jpeg::JpegEncoder, png::PngEncoder, webp::WebPEncoder},
+    ExtendedColorType, ImageBuffer, ImageEncoder, Rgba,
+};
+use std::{io::Write, path::Path};
+
+/**
+The quality JPEG images are encoded with when it is not given, between 1 and 100.
+ */
+pub const DEFAULT_JPEG_QUALITY: u8 = 75;
+
+/**
+An image format.
+
+
+## Jpeg
+
+Encoded with the quality, between 1 and 100. JPEG has no alpha channel, so it is left out.
+
+
+## WebP
+
+Encoded lossless.
+
+
+## Svg
+
+Only Maps created with new_svg can be written as SVG, and they can only be written as SVG.
+ */
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    Png,
+    Jpeg(u8),
+    WebP,
+    Bmp,
+    Svg,
+}
+
+impl Format {
+    /**
+    Returns the format matching the extension of the path. JPEG uses the default quality.
+
+
+    ## Errors
+
+    Returns an ImageError if the extension is missing or is not one of the formats.
+
+
+    ## Examples
+
+    ```
+    # use pathtracer::map::format::{Format, DEFAULT_JPEG_QUALITY};
+    # use std::path::Path;
+    assert_eq!(Format::from_path(Path::new("map.png")).unwrap(), Format::Png);
+    assert_eq!(Format::from_path(Path::new("map.jpg")).unwrap(), Format::Jpeg(DEFAULT_JPEG_QUALITY));
+    assert_eq!(Format::from_path(Path::new("map.svg")).unwrap(), Format::Svg);
+    assert!(Format::from_path(Path::new("map.tiff")).is_err());
+    ```
+     */
+    pub fn from_path(path: &Path) -> Result<Self, Error> {
+        let svg = path.extension().and_then(|x| x.to_str());
+        if svg.is_some_and(|x| x.eq_ignore_ascii_case("svg")) {
+            return Ok(Format::Svg);
+        }
+        let format = image::ImageFormat::from_path(path)?;
+        match format {
+            image::ImageFormat::Png => Ok(Format::Png),
+            image::ImageFormat::Jpeg => Ok(Format::Jpeg(DEFAULT_JPEG_QUALITY)),
+            image::ImageFormat::WebP => Ok(Format::WebP),
+            image::ImageFormat::Bmp => Ok(Format::Bmp),
+            _ => Err(image::ImageError::Unsupported(
+                image::error::UnsupportedError::from_format_and_kind(
+                    format.into(),
+                    image::error::UnsupportedErrorKind::Format(format.into()),
+                ),
+            )
+            .into()),
+        }
+    }
+
+    /**
+    Returns the file extension of the format, without the dot.
+     */
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Format::Png => "png",
+            Format::Jpeg(_) => "jpg",
+            Format::WebP => "webp",
+            Format::Bmp => "bmp",
+            Format::Svg => "svg",
+        }
+    }
+
+    /**
+    Returns the media type of the format, for example to be sent as a Content-Type.
+     */
+    pub fn mime_type(&self) -> &'static str {
+        match self {
+            Format::Png => "image/png",
+            Format::Jpeg(_) => "image/jpeg",
+            Format::WebP => "image/webp",
+            Format::Bmp => "image/bmp",
+            Format::Svg => "image/svg+xml",
+        }
+    }
+
+    /**
+    Encodes the image in the format, writing it to the writer.
+
+
+    ## Errors
+
+    Returns InvalidArgument for Svg, which raster images can not be encoded in,
+    or an ImageError if the image could not be encoded or written.
+
+
+    ## Examples
+
+    ```
+    # use pathtracer::map::format::Format;
+    let image = image::RgbaImage::new(4, 4);
+    let mut png = Vec::new();
+    Format::Png.encode(&image, &mut png).unwrap();
+    assert_eq!(&png[1..4], b"PNG");
+    ```
+     */
+    pub fn encode<W: Write>(
+        &self,
+        image: &ImageBuffer<Rgba<u8>, Vec<u8>>,
+        mut writer: W,
+    ) -> Result<(), Error> {
+        let (width, height) = image.dimensions();
+        match *self {
+            Format::Png => PngEncoder::new(writer).write_image(
+                image,
+                width,
+                height,
+                ExtendedColorType::Rgba8,
+            )?,
+            Format::Jpeg(quality) => {
+                let rgb = image::DynamicImage::ImageRgba8(image.clone()).to_rgb8();
+                JpegEncoder::new_with_quality(writer, quality.clamp(1, 100)).write_image(
+                    &rgb,
+                    width,
+                    height,
+                    ExtendedColorType::Rgb8,
+                )?
+            }
+            Format::WebP => WebPEncoder::new_lossless(writer).write_image(
+                image,
+                width,
+                height,
+                ExtendedColorType::Rgba8,
+            )?,
+            Format::Bmp => BmpEncoder::new(&mut writer).write_image(
+                image,
+                width,
+                height,
+                ExtendedColorType::Rgba8,
+            )?,
+            Format::Svg => {
+                return Err(Error::InvalidArgument(
+                    "raster images can not be encoded as SVG".to_string(),
+                ))
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encode_and_decode() {
+        let mut image = image::RgbaImage::new(3, 2);
+        image.put_pixel(1, 1, Rgba([200, 100, 50, 255]));
+        for format in [Format::Png, Format::WebP, Format::Bmp] {
+            let mut bytes = Vec::new();
+            format.encode(&image, &mut bytes).unwrap();
+            let decoded = image::load_from_memory(&bytes).unwrap().to_rgba8();
+            assert_eq!(decoded, image, "{:?}", format);
+        }
+
+        let mut bytes = Vec::new();
+        Format::Jpeg(90).encode(&image, &mut bytes).unwrap();
+        let decoded = image::load_from_memory(&bytes).unwrap();
+        assert_eq!(decoded.color(), image::ColorType::Rgb8);
+        assert_eq!((decoded.width(), decoded.height()), (3, 2));
+    }
+
+    #[test]
+    fn jpeg_quality() {
+        let image =
+            ImageBuffer::from_fn(64, 64, |x, y| Rgba([(x * 4) as u8, (y * 4) as u8, 0, 255]));
+        let size = |quality| {
+            let mut bytes = Vec::new();
+            Format::Jpeg(quality).encode(&image, &mut bytes).unwrap();
+            bytes.len()
+        };
+        assert!(size(10) < size(95));
+    }
+
+    #[test]
+    fn svg() {
+        assert_eq!(
+            Format::from_path(Path::new("map.SVG")).unwrap(),
+            Format::Svg
+        );
+        assert_eq!(Format::Svg.mime_type(), "image/svg+xml");
+        let image = image::RgbaImage::new(4, 4);
+        assert!(matches!(
+            Format::Svg.encode(&image, Vec::new()),
+            Err(Error::InvalidArgument(_))
+        ));
+    }
+}
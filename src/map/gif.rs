@@ -6,16 +6,23 @@
 
 use super::super::*;
 use gif::{self, *};
-use std::fs::File;
+pub use gif::{DisposalMethod, Repeat};
+use image::Rgba;
+use std::{cmp, collections::HashMap, fs::File, io::Write, path::Path};
 
-struct Cycle<'a, T: Draw + Location + Hash + MinMax + Copy> {
+/**
+The most frames animate and traverse push in a single call.
+ */
+pub const MAX_FRAMES: u32 = 100_000;
+
+struct Cycle<'a, T: Draw + Location + Hash + MinMax + Clone> {
     interval: u8,
     count: u8,
     map: Vec<T>,
     predicate: &'a dyn Fn(&T) -> T,
 }
 
-impl<'a, T: Draw + Location + Hash + MinMax + Copy> Cycle<'a, T> {
+impl<'a, T: Draw + Location + Hash + MinMax + Clone> Cycle<'a, T> {
     pub fn new(interval: u8, map: Vec<T>, predicate: &'a dyn Fn(&T) -> T) -> Self {
         Cycle {
             interval,
@@ -37,23 +44,181 @@ impl<'a, T: Draw + Location + Hash + MinMax + Copy> Cycle<'a, T> {
     }
 }
 
+/**
+Configures how a Gif is encoded.
+
+By default Gifs repeat forever, each frame is shown for 20 hundredths of a second and cleared to the background
+before the next, and every frame is quantized to its own palette at the best quality.
+
+
+## Palette
+
+A global palette is given as RGB triples, at most 256 of them. Frames are then drawn using the
+color in it which is nearest to each pixel, instead of a palette of their own.
+
+
+## Speed
+
+How fast the frames are quantized to their palettes, from 1 which gives the best colors to 30 which is the fastest.
+ */
+#[derive(Clone, Debug)]
+pub struct GifBuilder {
+    width: u16,
+    height: u16,
+    delay: u16,
+    repeat: Repeat,
+    dispose: DisposalMethod,
+    palette: Option<Vec<u8>>,
+    speed: i32,
+}
+
+impl GifBuilder {
+    /**
+    Constructs a builder for Gifs of the given size, using the defaults.
+     */
+    pub fn new(width: u16, height: u16) -> Self {
+        GifBuilder {
+            width,
+            height,
+            delay: 20,
+            repeat: Repeat::Infinite,
+            dispose: DisposalMethod::Background,
+            palette: None,
+            speed: 1,
+        }
+    }
+
+    /**
+    Sets how long each frame is shown, in hundredths of a second.
+     */
+    pub fn delay(mut self, delay: u16) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    /**
+    Sets how many times the Gif repeats after it is played. Repeat::Finite(0) plays it once.
+     */
+    pub fn repeat(mut self, repeat: Repeat) -> Self {
+        self.repeat = repeat;
+        self
+    }
+
+    /**
+    Sets what is done with a frame before the next one is drawn.
+     */
+    pub fn dispose(mut self, dispose: DisposalMethod) -> Self {
+        self.dispose = dispose;
+        self
+    }
+
+    /**
+    Sets a global palette of RGB triples, which every frame is drawn with.
+     */
+    pub fn palette(mut self, palette: &[u8]) -> Self {
+        self.palette = Some(palette.to_vec());
+        self
+    }
+
+    /**
+    Sets the speed of the quantization, clamped between 1 and 30.
+     */
+    pub fn speed(mut self, speed: i32) -> Self {
+        self.speed = speed.clamp(1, 30);
+        self
+    }
+
+    /**
+    Constructs a Gif which writes to the writer.
+
+
+    ## Errors
+
+    Returns a GifError if the header can not be written, or if the palette has more than 256 colors.
+    Returns InvalidArgument if the palette is empty, or its length is not a multiple of 3.
+
+
+    ## Examples
+
+    ```
+    # use pathtracer::{map::gif::*, *};
+    # fn main() -> Result<(), Error> {
+    let mut gif = GifBuilder::new(20, 20)
+        .delay(5)
+        .repeat(Repeat::Finite(2))
+        .build(Vec::new())?;
+    gif.blank()?;
+    let bytes = gif.finish()?;
+    assert_eq!(&bytes[..6], b"GIF89a");
+    # Ok(())
+    # }
+    ```
+     */
+    pub fn build<'a, W: Write>(self, writer: W) -> Result<Gif<'a, W>, Error> {
+        if let Some(palette) = &self.palette {
+            if palette.is_empty() || palette.len() % 3 != 0 {
+                return Err(Error::InvalidArgument(format!(
+                    "palette of {} bytes is not made of RGB triples",
+                    palette.len()
+                )));
+            }
+        }
+        let palette = self.palette.unwrap_or_default();
+        if palette.len() > 256 * 3 {
+            return Err(EncodingError::Format(EncodingFormatError::TooManyColors).into());
+        }
+        let mut encoder = Encoder::new(writer, self.width, self.height, &palette)?;
+        encoder.set_repeat(self.repeat)?;
+        Ok(Gif {
+            encoder,
+            cycles: Vec::new(),
+            frames: 0,
+            width: self.width,
+            height: self.height,
+            delay: self.delay,
+            dispose: self.dispose,
+            palette,
+            speed: self.speed,
+        })
+    }
+
+    /**
+    Constructs a Gif which is written to a file created at the path.
+
+
+    ## Errors
+
+    Returns Io if the file can not be created, or what build returns.
+     */
+    pub fn create<'a>(self, path: &Path) -> Result<Gif<'a, File>, Error> {
+        let file = File::create(path)?;
+        self.build(file)
+    }
+}
+
 /**
 Wrapper around the Gif struct found in the gif crate.
 
 With additions for pathtracer support, and cycling features.
 */
-pub struct Gif<'a> {
-    encoder: gif::Encoder<File>,
+pub struct Gif<'a, W: Write = File> {
+    encoder: gif::Encoder<W>,
     cycles: Vec<Cycle<'a, Node>>,
-    frames: u16,
+    frames: u32,
     width: u16,
     height: u16,
+    delay: u16,
+    dispose: DisposalMethod,
+    palette: Vec<u8>,
+    speed: i32,
 }
 
-impl<'a> Gif<'a> {
+impl<'a> Gif<'a, File> {
     /**
     Constructs a Gif struct and initializes a file on the system for the Gif to be stored.
 
+    Uses the defaults of GifBuilder.
+
 
     ## Errors
 
@@ -66,17 +231,29 @@ impl<'a> Gif<'a> {
     example/cycles.rs
 
     */
-    pub fn new(filename: &str, width: u16, height: u16) -> Self {
-        let file = File::create(filename).unwrap();
-        let mut encoder = Encoder::new(file, width, height, &[]).unwrap();
-        encoder.set_repeat(Repeat::Infinite).unwrap();
-        Gif {
-            encoder,
-            cycles: Vec::new(),
-            frames: 0,
-            width,
-            height,
-        }
+    pub fn new(filename: &str, width: u16, height: u16) -> Result<Self, Error> {
+        GifBuilder::new(width, height).create(Path::new(filename))
+    }
+}
+
+impl<'a, W: Write> Gif<'a, W> {
+    /**
+    Sets how long the frames pushed after this are shown, in hundredths of a second.
+     */
+    pub fn delay(&mut self, delay: u16) {
+        self.delay = delay;
+    }
+
+    /**
+    Writes the end of the Gif and returns the writer.
+
+
+    ## Errors
+
+    Returns Io if the writer fails.
+     */
+    pub fn finish(self) -> Result<W, Error> {
+        Ok(self.encoder.into_inner()?)
     }
 
     /**
@@ -88,7 +265,7 @@ impl<'a> Gif<'a> {
     examples/cycles.rs
     */
     pub fn cycle(&mut self, interval: u8, map: Vec<Node>) {
-        self.cycles.push(Cycle::new(interval, map, &|x| *x));
+        self.cycles.push(Cycle::new(interval, map, &|x| x.clone()));
     }
 
     /**
@@ -126,14 +303,14 @@ impl<'a> Gif<'a> {
     /**
     Returns the number of frames that has been written.
     */
-    pub fn frames(&self) -> u16 {
+    pub fn frames(&self) -> u32 {
         self.frames
     }
 
     /**
     Pushes a frame using a map struct.
     */
-    pub fn push(&mut self, mut map: Map) -> Result<(), gif::EncodingError> {
+    pub fn push(&mut self, mut map: Map) -> Result<(), Error> {
         map = map.map(&self.advance_cycle());
         self.push_frame(&map.consume())
     }
@@ -143,46 +320,706 @@ impl<'a> Gif<'a> {
 
     ## Errors
 
-    If the encoder fails to write the frame to disk.
+    InvalidArgument if the image is wider or taller than a Gif can be, 65535 pixels.
+    Otherwise if the encoder fails to write the frame to disk.
     */
-    pub fn push_frame(&mut self, image: &IW) -> Result<(), gif::EncodingError> {
-        let mut pixels: Vec<u8> = Vec::new();
-        for pix in image.image().pixels() {
-            // FIXME: this should be based on the number of channels
-            // so for RGB it would be 3, and for RGBA it would be 4.
-            // Hardcoding this value can cause some tricky to identify errors.
-            for i in 0..3 {
-                pixels.push(pix.0[i]);
+    pub fn push_frame(&mut self, image: &IW) -> Result<(), Error> {
+        let dim = image.dimensions();
+        let (width, height) = match (u16::try_from(dim.x), u16::try_from(dim.y)) {
+            (Ok(width), Ok(height)) => (width, height),
+            _ => {
+                return Err(Error::InvalidArgument(format!(
+                    "a {}x{} image is too large for a Gif frame",
+                    dim.x, dim.y
+                )))
+            }
+        };
+        let mut frame = if self.palette.is_empty() {
+            let mut pixels = image.image().as_raw().clone();
+            Frame::from_rgba_speed(width, height, &mut pixels, self.speed)
+        } else {
+            Frame::from_indexed_pixels(width, height, self.indexed(image), None)
+        };
+        frame.dispose = self.dispose;
+        frame.delay = self.delay;
+        self.encoder.write_frame(&frame)?;
+        self.frames = self.frames.saturating_add(1);
+        Ok(())
+    }
+
+    /**
+    Pushes the frames of the timeline, from its start to its last keyframe, at the frame rate.
+
+    Each frame draws the Groups and then the Nodes on a copy of the map, which should be given a size
+    so that every frame is drawn the same.
+
+
+    ## Errors
+
+    InvalidArgument if the timeline needs more than MAX_FRAMES frames at the frame rate.
+    Otherwise if the encoder fails to write a frame.
+
+
+    ## Examples
+
+    ```
+    # use pathtracer::{map::gif::*, *};
+    # fn main() -> Result<(), Error> {
+    let mut timeline = Timeline::new(Node::from_list(&[(10, 10)]), Vec::new());
+    timeline.key(
+        Target::Node(0),
+        Keyframe::new(1.0).geo(coordinate!(40, 40)).easing(Easing::Bounce),
+    )?;
+    let mut gif = GifBuilder::new(50, 50).build(Vec::new())?;
+    gif.animate(&timeline, 10, &Map::new().size(50, 50))?;
+    assert_eq!(gif.frames(), 11);
+    # Ok(())
+    # }
+    ```
+     */
+    pub fn animate(&mut self, timeline: &Timeline, fps: u32, map: &Map) -> Result<(), Error> {
+        let fps = fps.max(1);
+        let frames = (timeline.duration() * f64::from(fps)).round();
+        if frames >= f64::from(MAX_FRAMES) {
+            return Err(Error::InvalidArgument(format!(
+                "a timeline of {} seconds at {} frames per second needs more than {} frames",
+                timeline.duration(),
+                fps,
+                MAX_FRAMES
+            )));
+        }
+        let frames = frames as u32 + 1;
+        let delay = self.delay;
+        self.delay = (100.0 / f64::from(fps)).round().max(1.0) as u16;
+        let result = (0..frames).try_for_each(|i| {
+            let (nodes, groups) = timeline.at(f64::from(i) / f64::from(fps), &map.theme);
+            let mut frame = map.clone();
+            if !groups.is_empty() {
+                frame = frame.map(&groups);
             }
+            self.push(frame.map(&nodes))
+        });
+        self.delay = delay;
+        result
+    }
+
+    /**
+    Pushes the frames of a marker walking the path, such as one returned by Network::path, through the network.
+
+    The marker follows the edges between each pair of Nodes in the path as they are drawn, so curved and
+    routed edges are walked along their curves and corners. The last frame shows the marker at the end of the path.
+
+
+    ## Errors
+
+    InvalidArgument if the speed is not a positive number, or so slow that the walk needs more than MAX_FRAMES frames.
+    NoPath if the path is empty, or if two Nodes following each other in it are not linked, in either direction.
+    Otherwise if the encoder fails to write a frame.
+
+
+    ## Examples
+
+    ```
+    # use pathtracer::{map::gif::*, *};
+    # fn main() -> Result<(), Error> {
+    let nodes = Node::linked_list(Node::from_list(&[(0, 0), (40, 0), (40, 40)]));
+    let network = Network::new(nodes);
+    let path = network.path("A", "C")?;
+    let traversal = Traversal {
+        speed: 40.0,
+        fps: 2,
+        ..Traversal::default()
+    };
+    let mut gif = GifBuilder::new(60, 60).build(Vec::new())?;
+    gif.traverse(&network, &path, &traversal, &Map::new().size(60, 60).padding(10))?;
+    // 80 pixels at 20 pixels per frame, and the first frame.
+    assert_eq!(gif.frames(), 5);
+    # Ok(())
+    # }
+    ```
+     */
+    pub fn traverse(
+        &mut self,
+        network: &Network<Node>,
+        path: &[Node],
+        traversal: &Traversal,
+        map: &Map,
+    ) -> Result<(), Error> {
+        if !(traversal.speed.is_finite() && traversal.speed > 0.0) {
+            return Err(Error::InvalidArgument(format!(
+                "traversal speed must be positive, not {}",
+                traversal.speed
+            )));
+        }
+        if path.is_empty() {
+            return Err(Error::NoPath);
+        }
+        let mut links = Vec::new();
+        for pair in path.windows(2) {
+            let find = |f: &Node, t: &Node| {
+                network
+                    .hash_map
+                    .get(&f.hash)
+                    .and_then(|n| n.links.iter().find(|l| l.t == t.hash).copied())
+            };
+            let link = match find(&pair[0], &pair[1]) {
+                Some(link) => (link, false),
+                None => (find(&pair[1], &pair[0]).ok_or(Error::NoPath)?, true),
+            };
+            links.push(link);
         }
 
-        let dim = image.dimensions();
-        let mut frame = Frame::from_rgb(dim.x as u16, dim.y as u16, &pixels);
-        frame.dispose = DisposalMethod::Background;
-        frame.delay = 20;
-        self.encoder.write_frame(&frame)?;
-        self.frames += 1;
+        let delay = self.delay;
+        self.delay = (100.0 / f64::from(traversal.fps.max(1))).round().max(1.0) as u16;
+        let result = self.walk(network, path, &links, traversal, map);
+        self.delay = delay;
+        result
+    }
+
+    /**
+    Pushes the frames of traverse, walking the links between the Nodes in the path.
+
+    A path of a single Node gives a single frame, with the marker on the Node.
+     */
+    fn walk(
+        &mut self,
+        network: &Network<Node>,
+        path: &[Node],
+        links: &[(HL, bool)],
+        traversal: &Traversal,
+        map: &Map,
+    ) -> Result<(), Error> {
+        let step = traversal.speed / f64::from(traversal.fps.max(1));
+        let (image, add) = traversal_frame(network, path, traversal, map, 0)?;
+        let lines = links
+            .iter()
+            .map(|(link, reversed)| {
+                let line = walked_line(&image, add, link, *reversed);
+                let length = line
+                    .windows(2)
+                    .map(|w| (w[1].x - w[0].x).hypot(w[1].y - w[0].y))
+                    .sum::<f64>();
+                (line, length)
+            })
+            .collect::<Vec<_>>();
+        let total = lines.iter().map(|(_, length)| length).sum::<f64>();
+        let shift = coordinate!(link_shift(&image, None));
+        let origin = Point::from(image.project(path[0].geo + Point::from(add)) + shift);
+        let frames = (total / step).ceil();
+        if frames >= f64::from(MAX_FRAMES) {
+            return Err(Error::InvalidArgument(format!(
+                "traversal speed {} needs more than {} frames",
+                traversal.speed, MAX_FRAMES
+            )));
+        }
+        let frames = frames as u32 + 1;
+
+        // The frame under the walk only changes when another edge is walked completely.
+        let mut base = (0, image);
+        for frame in 0..frames {
+            let walked = (f64::from(frame) * step).min(total);
+
+            // Edges walked completely are highlighted, which does not move the Nodes the lines are routed around.
+            let mut end = 0.0;
+            let done = lines
+                .iter()
+                .take_while(|(_, length)| {
+                    end += length;
+                    end <= walked
+                })
+                .count();
+            if done != base.0 {
+                base = (
+                    done,
+                    traversal_frame(network, path, traversal, map, done)?.0,
+                );
+            }
+            let mut image = base.1.clone();
+
+            let mut start = 0.0;
+            let mut marker = origin;
+            for (line, length) in &lines {
+                if walked >= start {
+                    let trail = map::raster::slice(line, 0.0, walked - start);
+                    if walked < start + length {
+                        let width = f64::from(traversal.width);
+                        map::raster::polyline(&mut image, &trail, width, traversal.paint);
+                    }
+                    marker = trail.last().copied().unwrap_or(marker);
+                }
+                start += length;
+            }
+            let radius = f64::from(traversal.radius);
+            map::raster::circle(&mut image, marker, radius, traversal.marker);
+            self.push_frame(&image)?;
+        }
         Ok(())
     }
 
+    /**
+    Returns the index of the nearest color in the global palette for each pixel.
+     */
+    fn indexed(&self, image: &IW) -> Vec<u8> {
+        let mut nearest: HashMap<[u8; 3], u8> = HashMap::new();
+        image
+            .image()
+            .pixels()
+            .map(|p| {
+                let rgb = [p.0[0], p.0[1], p.0[2]];
+                *nearest.entry(rgb).or_insert_with(|| {
+                    let distance = |c: &[u8]| {
+                        c.iter()
+                            .zip(rgb.iter())
+                            .map(|(&a, &b)| (i32::from(a) - i32::from(b)).pow(2))
+                            .sum::<i32>()
+                    };
+                    self.palette
+                        .chunks(3)
+                        .enumerate()
+                        .min_by_key(|(_, c)| distance(c))
+                        .map_or(0, |(i, _)| i as u8)
+                })
+            })
+            .collect()
+    }
+
     /**
     Appends a blank frame to the gif.
 
     This will also advance Gif cycles.
     */
-    pub fn blank(&mut self) -> Result<(), gif::EncodingError> {
-        let mut node = node!(self.width as i16 - 1, self.height as i16 - 1);
+    pub fn blank(&mut self) -> Result<(), Error> {
+        let mut node = node!(self.width as i32 - 1, self.height as i32 - 1);
         node.radius = Some(0);
         self.push(Map::new().map(&[node]))
     }
 }
 
+/**
+Configures an animation of a marker walking a path through a Network.
+
+
+## Speed
+
+How far the marker moves each second, in pixels along the edges.
+
+
+## Paint and width
+
+The edges the marker has walked are drawn with the paint and width, growing behind it.
+
+
+## Marker
+
+The color and radius of the marker.
+ */
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Traversal {
+    pub speed: f64,
+    pub fps: u32,
+    pub paint: map::theme::Paint,
+    pub width: u32,
+    pub marker: Rgba<u8>,
+    pub radius: u32,
+}
+
+impl Default for Traversal {
+    /**
+    Walks 100 pixels each second at 25 frames per second, with a white marker and a red trail.
+     */
+    fn default() -> Self {
+        Traversal {
+            speed: 100.0,
+            fps: 25,
+            paint: Rgba([230, 40, 40, 255]).into(),
+            width: 3,
+            marker: Rgba([255, 255, 255, 255]),
+            radius: 4,
+        }
+    }
+}
+
+/**
+Draws the network, with the links between the first done + 1 Nodes of the path highlighted.
+
+Returns the image and the offset the elements were drawn with.
+ */
+fn traversal_frame(
+    network: &Network<Node>,
+    path: &[Node],
+    traversal: &Traversal,
+    map: &Map,
+    done: usize,
+) -> Result<(IW, Coordinate), Error> {
+    let mut network = network.clone();
+    let walked_path = &path[..cmp::min(done + 1, path.len())];
+    network.highlight(walked_path, traversal.paint, traversal.width);
+    let mut nodes = network.hash_map.into_values().collect::<Vec<_>>();
+    nodes.sort_by_key(|n| n.hash);
+    let drawn = map.clone().map(&nodes);
+    let add = drawn.add;
+    Ok((drawn.image.ok_or(Error::NotMapped)?, add))
+}
+
+/**
+Returns how far aliased links of the width are moved from the line between their ends when drawn.
+ */
+fn link_shift(image: &IW, width: Option<u32>) -> u32 {
+    match image.rasterizer() {
+        map::raster::Rasterizer::AntiAliased => 0,
+        _ => width.unwrap_or(image.theme().link_width) / 2,
+    }
+}
+
+/**
+Returns the points of the link as it is drawn on the image, going from the start of the walk to its end.
+ */
+fn walked_line(image: &IW, add: Coordinate, link: &HL, reversed: bool) -> Vec<Point> {
+    let (from, to) = link.min_max();
+    let from = image.project(Point::from(from + add));
+    let to = image.project(Point::from(to + add));
+    let shift = coordinate!(link_shift(image, link.width));
+    let mut line = link
+        .polyline(from, to, image.obstacles())
+        .into_iter()
+        .map(|c| Point::from(c + shift))
+        .collect::<Vec<_>>();
+    if reversed {
+        line.reverse();
+    }
+    line
+}
+
+/**
+How the values change between two keyframes.
+
+
+## Linear
+
+At a constant rate.
+
+
+## EaseInOut
+
+Slowly at first and at the end, and fastest halfway.
+
+
+## Bounce
+
+Reaches the end early and bounces back from it a few times, lower each time, until it settles.
+ */
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Easing {
+    #[default]
+    Linear,
+    EaseInOut,
+    Bounce,
+}
+
+impl Easing {
+    /**
+    Returns the eased progress for the progress t, between 0 and 1.
+
+
+    ## Examples
+
+    ```
+    # use pathtracer::map::gif::Easing;
+    assert_eq!(Easing::Linear.ease(0.25), 0.25);
+    assert!(Easing::EaseInOut.ease(0.25) < 0.25);
+    assert_eq!(Easing::Bounce.ease(1.0), 1.0);
+    ```
+     */
+    pub fn ease(&self, t: f64) -> f64 {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            Easing::Linear => t,
+            Easing::EaseInOut => {
+                if t < 0.5 {
+                    4.0 * t * t * t
+                } else {
+                    1.0 - (2.0 - 2.0 * t).powi(3) / 2.0
+                }
+            }
+            Easing::Bounce => {
+                const N: f64 = 7.5625;
+                const D: f64 = 2.75;
+                if t < 1.0 / D {
+                    N * t * t
+                } else if t < 2.0 / D {
+                    let t = t - 1.5 / D;
+                    N * t * t + 0.75
+                } else if t < 2.5 / D {
+                    let t = t - 2.25 / D;
+                    N * t * t + 0.9375
+                } else {
+                    let t = t - 2.625 / D;
+                    N * t * t + 0.984375
+                }
+            }
+        }
+    }
+}
+
+/**
+The values an element has at a time, in seconds from the start of the Timeline.
+
+Values which are left out keep changing towards the next keyframe which has them, or stay as they are after the last.
+The easing is used when changing from the keyframe before to this one.
+ */
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Keyframe {
+    pub time: f64,
+    pub geo: Option<Point>,
+    pub color: Option<Rgba<u8>>,
+    pub radius: Option<u32>,
+    pub easing: Easing,
+}
+
+impl Keyframe {
+    /**
+    Constructs a keyframe at the time which changes nothing.
+     */
+    pub fn new(time: f64) -> Self {
+        Keyframe {
+            time,
+            ..Keyframe::default()
+        }
+    }
+
+    /**
+    Sets the position of the element.
+     */
+    pub fn geo<P: Into<Point>>(mut self, geo: P) -> Self {
+        self.geo = Some(geo.into());
+        self
+    }
+
+    /**
+    Sets the color of the element.
+     */
+    pub fn color(mut self, color: Rgba<u8>) -> Self {
+        self.color = Some(color);
+        self
+    }
+
+    /**
+    Sets the radius of the element, or the width of an edge.
+     */
+    pub fn radius(mut self, radius: u32) -> Self {
+        self.radius = Some(radius);
+        self
+    }
+
+    /**
+    Sets how the values change when arriving at the keyframe.
+     */
+    pub fn easing(mut self, easing: Easing) -> Self {
+        self.easing = easing;
+        self
+    }
+}
+
+/**
+An element in a Timeline which keyframes are added to.
+
+
+## Node
+
+The Node at the index.
+
+
+## Group
+
+The Group at the index. Its color is given to all of its Nodes, while its radius only changes the Group's meta-data.
+
+
+## Edge
+
+The link at the second index of the Node at the first. Positions are ignored, edges follow their Nodes.
+ */
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Target {
+    Node(usize),
+    Group(usize),
+    Edge(usize, usize),
+}
+
+/**
+Nodes and Groups which change over time by keyframes.
+
+The values at a time are interpolated between the keyframes before and after it, starting
+from the values the elements were given at time 0.
+
+
+## Examples
+
+```
+# use pathtracer::{map::{gif::*, theme::Theme}, *};
+# fn main() -> Result<(), Error> {
+let nodes = Node::linked_list(Node::from_list(&[(0, 0), (100, 0)]));
+let mut timeline = Timeline::new(nodes, Vec::new());
+timeline.key(Target::Node(1), Keyframe::new(2.0).geo(coordinate!(100, 100)))?;
+let (nodes, _) = timeline.at(1.0, &Theme::default());
+assert_eq!(nodes[1].geo, Point::new(100.0, 50.0));
+// The link from the moved Node follows it.
+assert_eq!(nodes[1].hl(0)?.from, Some(coordinate!(100, 50)));
+# Ok(())
+# }
+```
+ */
+#[derive(Clone, Debug, Default)]
+pub struct Timeline {
+    nodes: Vec<Node>,
+    groups: Vec<Group>,
+    keyframes: Vec<(Target, Keyframe)>,
+}
+
+impl Timeline {
+    /**
+    Constructs a Timeline of the elements as they are at time 0.
+     */
+    pub fn new(nodes: Vec<Node>, groups: Vec<Group>) -> Self {
+        Timeline {
+            nodes,
+            groups,
+            keyframes: Vec::new(),
+        }
+    }
+
+    /**
+    Adds a keyframe to the target.
+
+
+    ## Errors
+
+    InvalidArgument if the time of the keyframe is negative or not finite.
+     */
+    pub fn key(&mut self, target: Target, keyframe: Keyframe) -> Result<(), Error> {
+        if !(keyframe.time.is_finite() && keyframe.time >= 0.0) {
+            return Err(Error::InvalidArgument(format!(
+                "keyframe time must be a finite number of seconds from 0, not {}",
+                keyframe.time
+            )));
+        }
+        let index = self
+            .keyframes
+            .partition_point(|(_, k)| k.time <= keyframe.time);
+        self.keyframes.insert(index, (target, keyframe));
+        Ok(())
+    }
+
+    /**
+    Returns the time of the last keyframe, in seconds.
+     */
+    pub fn duration(&self) -> f64 {
+        self.keyframes.last().map_or(0.0, |(_, k)| k.time)
+    }
+
+    /**
+    Returns the Nodes and Groups as they are at the time, in seconds.
+
+    Edges without a paint change color starting from the edge color the theme draws them in.
+     */
+    pub fn at(&self, time: f64, theme: &map::theme::Theme) -> (Vec<Node>, Vec<Group>) {
+        let mut nodes = self.nodes.clone();
+        for (i, node) in nodes.iter_mut().enumerate() {
+            let target = Target::Node(i);
+            if let Some(geo) = self.track(target, time, node.geo, |k| k.geo, lerp) {
+                node.geo = geo;
+            }
+            let color = node.color.unwrap_or(consts::DEFAULT_RGBA);
+            if let Some(color) = self.track(target, time, color, |k| k.color, mix) {
+                node.color = Some(color);
+            }
+            let radius = node.radius.unwrap_or(u32::from(consts::DEFAULT_SIZE));
+            if let Some(radius) = self.track(target, time, radius, |k| k.radius, scale) {
+                node.radius = Some(radius);
+            }
+            for (j, link) in node.links.iter_mut().enumerate() {
+                let target = Target::Edge(i, j);
+                let width = link.width.unwrap_or(u32::from(consts::DEFAULT_LINK_SIZE));
+                if let Some(width) = self.track(target, time, width, |k| k.radius, scale) {
+                    link.width = Some(width);
+                }
+                let color = link.paint.unwrap_or_else(|| theme.edge(width)).at(0.0);
+                if let Some(color) = self.track(target, time, color, |k| k.color, mix) {
+                    link.paint = Some(map::theme::Paint::Solid(color));
+                }
+            }
+        }
+        layout::relink(&mut nodes);
+
+        let mut groups = self.groups.clone();
+        for (i, group) in groups.iter_mut().enumerate() {
+            let target = Target::Group(i);
+            if let Some(geo) = self.track(target, time, group.settings.geo, |k| k.geo, lerp) {
+                group.settings.geo = geo;
+            }
+            let color = group.settings.color.unwrap_or(consts::DEFAULT_RGBA);
+            if let Some(color) = self.track(target, time, color, |k| k.color, mix) {
+                group.settings.color = Some(color);
+                group.each(&|node: &mut Node| node.color = Some(color));
+            }
+            let radius = group.dynamic_radius();
+            if let Some(radius) = self.track(target, time, radius, |k| k.radius, scale) {
+                group.radius(radius);
+            }
+        }
+        (nodes, groups)
+    }
+
+    /**
+    Returns a value of the target at the time, or None if no keyframe of the target has it.
+     */
+    fn track<V: Copy>(
+        &self,
+        target: Target,
+        time: f64,
+        initial: V,
+        value: impl Fn(&Keyframe) -> Option<V>,
+        interpolate: impl Fn(V, V, f64) -> V,
+    ) -> Option<V> {
+        let mut keys = self
+            .keyframes
+            .iter()
+            .filter(|(t, _)| *t == target)
+            .filter_map(|(_, k)| value(k).map(|v| (k, v)))
+            .peekable();
+        keys.peek()?;
+        let (mut from_time, mut from) = (0.0, initial);
+        for (key, to) in keys {
+            if key.time <= time {
+                (from_time, from) = (key.time, to);
+                continue;
+            }
+            let t = (time - from_time) / (key.time - from_time);
+            return Some(interpolate(from, to, key.easing.ease(t)));
+        }
+        Some(from)
+    }
+}
+
+fn lerp(a: Point, b: Point, t: f64) -> Point {
+    Point::new(a.x + (b.x - a.x) * t, a.y + (b.y - a.y) * t)
+}
+
+fn mix(a: Rgba<u8>, b: Rgba<u8>, t: f64) -> Rgba<u8> {
+    map::theme::Paint::Gradient(a, b).at(t)
+}
+
+fn scale(a: u32, b: u32, t: f64) -> u32 {
+    (f64::from(a) + (f64::from(b) - f64::from(a)) * t)
+        .round()
+        .max(0.0) as u32
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     fn define(case: &dyn Fn(Gif) -> std::io::Result<()>) {
-        let gif = Gif::new("/tmp/test_gif_new.gif", 50, 50);
+        let gif = Gif::new("/tmp/test_gif_new.gif", 50, 50).unwrap();
         case(gif).unwrap();
         // This can break Travis CI. Because the file doesn't get created?
         //let _ = std::fs::remove_file("test_gif_new.gif").unwrap();
@@ -193,6 +1030,281 @@ mod tests {
         define(&|_| Ok(()));
     }
 
+    #[test]
+    fn builder_options() {
+        let palette = [0, 0, 0, 255, 255, 255];
+        let mut gif = GifBuilder::new(20, 20)
+            .delay(7)
+            .dispose(DisposalMethod::Keep)
+            .palette(&palette)
+            .build(Vec::new())
+            .unwrap();
+        gif.blank().unwrap();
+        gif.delay(3);
+        gif.blank().unwrap();
+        let bytes = gif.finish().unwrap();
+
+        let mut decoder = gif::DecodeOptions::new().read_info(&bytes[..]).unwrap();
+        assert_eq!(decoder.global_palette(), Some(&palette[..]));
+        let mut delays = Vec::new();
+        while let Some(frame) = decoder.read_next_frame().unwrap() {
+            assert_eq!(frame.dispose, DisposalMethod::Keep);
+            assert!(frame.palette.is_none());
+            assert!(frame.buffer.iter().all(|&i| i < 2));
+            delays.push(frame.delay);
+        }
+        assert_eq!(delays, vec![7, 3]);
+    }
+
+    #[test]
+    fn builder_errors() {
+        assert!(GifBuilder::new(10, 10)
+            .palette(&[0; 257 * 3])
+            .build(Vec::new())
+            .is_err());
+        for palette in [&[][..], &[0, 0, 0, 255]] {
+            assert!(matches!(
+                GifBuilder::new(10, 10).palette(palette).build(Vec::new()),
+                Err(Error::InvalidArgument(_))
+            ));
+        }
+        assert!(GifBuilder::new(10, 10)
+            .create(Path::new("/nonexistent/out.gif"))
+            .is_err());
+    }
+
+    #[test]
+    fn easing_ends() {
+        for easing in [Easing::Linear, Easing::EaseInOut, Easing::Bounce] {
+            assert_eq!(easing.ease(0.0), 0.0);
+            assert!((easing.ease(1.0) - 1.0).abs() < 1e-9);
+            assert_eq!(easing.ease(2.0), easing.ease(1.0));
+        }
+        assert!((Easing::EaseInOut.ease(0.5) - 0.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn timeline_interpolates() {
+        let nodes = Node::linked_list(Node::from_list(&[(0, 0), (10, 0)]));
+        let mut group = Group::new("", coordinate!(50, 50));
+        group.push(node!(50, 60));
+        let mut timeline = Timeline::new(nodes, vec![group]);
+        let red = Rgba([200, 0, 0, 255]);
+        timeline
+            .key(Target::Node(0), Keyframe::new(1.0).radius(2).color(red))
+            .unwrap();
+        timeline
+            .key(Target::Node(0), Keyframe::new(2.0).radius(12))
+            .unwrap();
+        timeline
+            .key(Target::Edge(1, 0), Keyframe::new(2.0).radius(5).color(red))
+            .unwrap();
+        timeline
+            .key(
+                Target::Group(0),
+                Keyframe::new(2.0).geo(coordinate!(70, 50)),
+            )
+            .unwrap();
+        assert_eq!(timeline.duration(), 2.0);
+
+        // Edges without a paint start from the edge color of the theme.
+        let theme = map::theme::Theme::light();
+        let (nodes, _) = timeline.at(1.0, &theme);
+        assert_eq!(
+            nodes[1].links[0].paint,
+            Some(map::theme::Paint::Solid(Rgba([185, 85, 85, 255])))
+        );
+
+        let (nodes, groups) = timeline.at(0.5, &theme);
+        assert_eq!(nodes[0].radius, Some(3));
+        assert_eq!(nodes[0].color, Some(Rgba([100, 0, 0, 255])));
+        assert_eq!(nodes[1].color, None);
+        assert_eq!(groups[0].position(), coordinate!(55, 50));
+        assert_eq!(groups[0].nodes[0].position(), coordinate!(0, 10));
+
+        let (nodes, _) = timeline.at(1.5, &theme);
+        assert_eq!(nodes[0].radius, Some(7));
+        assert_eq!(nodes[0].color, Some(red));
+        let (nodes, _) = timeline.at(3.0, &theme);
+        assert_eq!(nodes[0].radius, Some(12));
+        assert_eq!(nodes[1].links[0].width, Some(5));
+        assert_eq!(nodes[1].links[0].paint, Some(map::theme::Paint::Solid(red)));
+    }
+
+    #[test]
+    fn timeline_group_radius() {
+        let mut group = Group::new("", coordinate!(50, 50));
+        group.radius(40);
+        group.add(10);
+        let sizes = |g: &Group| g.nodes.iter().map(|n| n.radius).collect::<Vec<_>>();
+        let before = sizes(&group);
+        let mut timeline = Timeline::new(Vec::new(), vec![group]);
+        timeline
+            .key(Target::Group(0), Keyframe::new(1.0).radius(60))
+            .unwrap();
+
+        let (_, groups) = timeline.at(0.5, &map::theme::Theme::default());
+        assert_eq!(groups[0].dynamic_radius(), 50);
+        assert_eq!(sizes(&groups[0]), before);
+    }
+
+    #[test]
+    fn frame_too_large() {
+        let mut gif = GifBuilder::new(10, 10).build(Vec::new()).unwrap();
+        let image = Map::new().size(70_000, 1).map::<Node>(&[]).consume();
+        let result = gif.push_frame(&image);
+        assert!(matches!(result, Err(Error::InvalidArgument(_))));
+        assert_eq!(gif.frames(), 0);
+    }
+
+    #[test]
+    fn timeline_invalid_times() {
+        let mut timeline = Timeline::new(Node::from_list(&[(0, 0)]), Vec::new());
+        for time in [-1.0, f64::NAN, f64::INFINITY] {
+            let result = timeline.key(Target::Node(0), Keyframe::new(time));
+            assert!(matches!(result, Err(Error::InvalidArgument(_))), "{}", time);
+        }
+        assert_eq!(timeline.duration(), 0.0);
+
+        timeline.key(Target::Node(0), Keyframe::new(1e12)).unwrap();
+        let mut gif = GifBuilder::new(30, 30).build(Vec::new()).unwrap();
+        let result = gif.animate(&timeline, 30, &Map::new().size(30, 30));
+        assert!(matches!(result, Err(Error::InvalidArgument(_))));
+        let result = gif.animate(&timeline, u32::MAX, &Map::new().size(30, 30));
+        assert!(matches!(result, Err(Error::InvalidArgument(_))));
+        assert_eq!(gif.frames(), 0);
+    }
+
+    #[test]
+    fn animate_restores_delay() {
+        let mut timeline = Timeline::new(Node::from_list(&[(0, 0)]), Vec::new());
+        timeline
+            .key(Target::Node(0), Keyframe::new(0.5).geo(coordinate!(20, 20)))
+            .unwrap();
+        let mut gif = GifBuilder::new(30, 30).delay(9).build(Vec::new()).unwrap();
+        gif.animate(&timeline, 4, &Map::new().size(30, 30)).unwrap();
+        gif.blank().unwrap();
+        let bytes = gif.finish().unwrap();
+
+        let mut decoder = gif::DecodeOptions::new().read_info(&bytes[..]).unwrap();
+        let mut delays = Vec::new();
+        while let Some(frame) = decoder.read_next_frame().unwrap() {
+            delays.push(frame.delay);
+        }
+        assert_eq!(delays, vec![25, 25, 25, 9]);
+    }
+
+    fn decode(bytes: &[u8]) -> Vec<Vec<u8>> {
+        let mut options = gif::DecodeOptions::new();
+        options.set_color_output(gif::ColorOutput::RGBA);
+        let mut decoder = options.read_info(bytes).unwrap();
+        let mut frames = Vec::new();
+        while let Some(frame) = decoder.read_next_frame().unwrap() {
+            frames.push(frame.buffer.to_vec());
+        }
+        frames
+    }
+
+    #[test]
+    fn traverse_path() {
+        let nodes = Node::linked_list(Node::from_list(&[(0, 0), (40, 0), (40, 40)]));
+        let network = Network::new(nodes);
+        let path = network.path("A", "C").unwrap();
+        let traversal = Traversal {
+            speed: 20.0,
+            fps: 1,
+            radius: 2,
+            ..Traversal::default()
+        };
+        let map = Map::new().size(60, 60).padding(10);
+        let mut gif = GifBuilder::new(60, 60).build(Vec::new()).unwrap();
+        gif.traverse(&network, &path, &traversal, &map).unwrap();
+        assert_eq!(gif.frames(), 5);
+
+        let frames = decode(&gif.finish().unwrap());
+        let pixel = |frame: &[u8], x: usize, y: usize| {
+            let i = (y * 60 + x) * 4;
+            [frame[i], frame[i + 1], frame[i + 2]]
+        };
+        let red = |p: [u8; 3]| p[0] > 150 && p[1] < 100;
+        let white = |p: [u8; 3]| p.iter().all(|&c| c > 200);
+        // The path goes from C to A. The marker is halfway along the first edge, with the trail behind it and none in front.
+        assert!(white(pixel(&frames[1], 50, 30)));
+        assert!(red(pixel(&frames[1], 50, 40)));
+        assert!(!red(pixel(&frames[1], 50, 20)));
+        // At the end both edges are walked.
+        assert!(white(pixel(&frames[4], 10, 10)));
+        assert!(red(pixel(&frames[4], 30, 10)));
+        assert!(red(pixel(&frames[4], 50, 30)));
+    }
+
+    #[test]
+    fn traverse_invalid_speed() {
+        let network = Network::new(Node::linked_list(Node::from_list(&[(0, 0), (40, 0)])));
+        let path = network.path("A", "B").unwrap();
+        let mut gif = GifBuilder::new(60, 60).build(Vec::new()).unwrap();
+        let map = Map::new().size(60, 60);
+        for speed in [0.0, -5.0, f64::NAN, f64::INFINITY, 1e-9, f64::MIN_POSITIVE] {
+            let traversal = Traversal {
+                speed,
+                ..Traversal::default()
+            };
+            let result = gif.traverse(&network, &path, &traversal, &map);
+            assert!(
+                matches!(result, Err(Error::InvalidArgument(_))),
+                "{}",
+                speed
+            );
+        }
+        assert!(matches!(
+            gif.traverse(&network, &[], &Traversal::default(), &map),
+            Err(Error::NoPath)
+        ));
+        assert_eq!(gif.frames(), 0);
+    }
+
+    #[test]
+    fn traverse_frame_count() {
+        let network = Network::new(Node::linked_list(Node::from_list(&[(0, 0), (80, 0)])));
+        let path = network.path("A", "B").unwrap();
+        let traversal = Traversal {
+            speed: 30.0,
+            fps: 1,
+            ..Traversal::default()
+        };
+        let mut gif = GifBuilder::new(100, 30).build(Vec::new()).unwrap();
+        let map = Map::new().size(100, 30).padding(10);
+        gif.traverse(&network, &path, &traversal, &map).unwrap();
+        // 30, 60 and the end at 80, after the first frame.
+        assert_eq!(gif.frames(), 4);
+    }
+
+    #[test]
+    fn traverse_single_node() {
+        let network = Network::new(Node::from_list(&[(20, 20)]));
+        let path = vec![network.get("A").unwrap()];
+        let mut gif = GifBuilder::new(60, 60).build(Vec::new()).unwrap();
+        let map = Map::new().size(60, 60).padding(10);
+        gif.traverse(&network, &path, &Traversal::default(), &map)
+            .unwrap();
+        assert_eq!(gif.frames(), 1);
+
+        let frames = decode(&gif.finish().unwrap());
+        let i = (30 * 60 + 30) * 4;
+        assert!(frames[0][i..i + 3].iter().all(|&c| c > 200));
+    }
+
+    #[test]
+    fn traverse_unlinked() {
+        let network = Network::new(Node::from_list(&[(0, 0), (40, 0)]));
+        let path = Node::from_list(&[(0, 0), (40, 0)]);
+        let mut gif = GifBuilder::new(60, 60).build(Vec::new()).unwrap();
+        let map = Map::new().size(60, 60);
+        let result = gif.traverse(&network, &path, &Traversal::default(), &map);
+        assert!(matches!(result, Err(Error::NoPath)));
+        assert_eq!(gif.frames(), 0);
+    }
+
     #[test]
     fn blank_frames() {
         define(&|mut gif| {
@@ -207,8 +1319,8 @@ mod tests {
     fn cycles_predicate() {
         define(&|mut gif| {
             gif.cycle_predicate(1, vec![node!(25, 25)], &|x| {
-                let mut x = *x;
-                x.geo.x += 5;
+                let mut x = x.clone();
+                x.geo.x += 5.0;
                 x
             });
 
@@ -0,0 +1,356 @@
+/*!
+Text labels drawn next to Nodes and Groups.
+
+Text is drawn with a built-in 5x7 bitmap font covering printable ASCII, so no system fonts are required.
+Characters outside of it are drawn as '?'.
+
+Labels are placed on the preferred side of what they belong to. When that would overlap a label already
+drawn, the other sides are tried in turn, and the label is left out if none of them are free.
+ */
+
+use super::super::*;
+use image::Rgba;
+
+/// Width of a character in pixels, without the space between characters.
+const GLYPH_WIDTH: i32 = 5;
+
+/// Height of a character in pixels.
+const GLYPH_HEIGHT: i32 = 7;
+
+/// Pixels left between a label and what it belongs to.
+const MARGIN: i32 = 2;
+
+/**
+Columns of the printable ASCII characters from ' ' to '~', the lowest bit being the top row.
+ */
+const GLYPHS: [[u8; 5]; 95] = [
+    [0x00, 0x00, 0x00, 0x00, 0x00],
+    [0x00, 0x00, 0x5F, 0x00, 0x00],
+    [0x00, 0x07, 0x00, 0x07, 0x00],
+    [0x14, 0x7F, 0x14, 0x7F, 0x14],
+    [0x24, 0x2A, 0x7F, 0x2A, 0x12],
+    [0x23, 0x13, 0x08, 0x64, 0x62],
+    [0x36, 0x49, 0x55, 0x22, 0x50],
+    [0x00, 0x05, 0x03, 0x00, 0x00],
+    [0x00, 0x1C, 0x22, 0x41, 0x00],
+    [0x00, 0x41, 0x22, 0x1C, 0x00],
+    [0x14, 0x08, 0x3E, 0x08, 0x14],
+    [0x08, 0x08, 0x3E, 0x08, 0x08],
+    [0x00, 0x50, 0x30, 0x00, 0x00],
+    [0x08, 0x08, 0x08, 0x08, 0x08],
+    [0x00, 0x60, 0x60, 0x00, 0x00],
+    [0x20, 0x10, 0x08, 0x04, 0x02],
+    [0x3E, 0x51, 0x49, 0x45, 0x3E],
+    [0x00, 0x42, 0x7F, 0x40, 0x00],
+    [0x42, 0x61, 0x51, 0x49, 0x46],
+    [0x21, 0x41, 0x45, 0x4B, 0x31],
+    [0x18, 0x14, 0x12, 0x7F, 0x10],
+    [0x27, 0x45, 0x45, 0x45, 0x39],
+    [0x3C, 0x4A, 0x49, 0x49, 0x30],
+    [0x01, 0x71, 0x09, 0x05, 0x03],
+    [0x36, 0x49, 0x49, 0x49, 0x36],
+    [0x06, 0x49, 0x49, 0x29, 0x1E],
+    [0x00, 0x36, 0x36, 0x00, 0x00],
+    [0x00, 0x56, 0x36, 0x00, 0x00],
+    [0x08, 0x14, 0x22, 0x41, 0x00],
+    [0x14, 0x14, 0x14, 0x14, 0x14],
+    [0x00, 0x41, 0x22, 0x14, 0x08],
+    [0x02, 0x01, 0x51, 0x09, 0x06],
+    [0x32, 0x49, 0x79, 0x41, 0x3E],
+    [0x7E, 0x11, 0x11, 0x11, 0x7E],
+    [0x7F, 0x49, 0x49, 0x49, 0x36],
+    [0x3E, 0x41, 0x41, 0x41, 0x22],
+    [0x7F, 0x41, 0x41, 0x22, 0x1C],
+    [0x7F, 0x49, 0x49, 0x49, 0x41],
+    [0x7F, 0x09, 0x09, 0x09, 0x01],
+    [0x3E, 0x41, 0x49, 0x49, 0x7A],
+    [0x7F, 0x08, 0x08, 0x08, 0x7F],
+    [0x00, 0x41, 0x7F, 0x41, 0x00],
+    [0x20, 0x40, 0x41, 0x3F, 0x01],
+    [0x7F, 0x08, 0x14, 0x22, 0x41],
+    [0x7F, 0x40, 0x40, 0x40, 0x40],
+    [0x7F, 0x02, 0x0C, 0x02, 0x7F],
+    [0x7F, 0x04, 0x08, 0x10, 0x7F],
+    [0x3E, 0x41, 0x41, 0x41, 0x3E],
+    [0x7F, 0x09, 0x09, 0x09, 0x06],
+    [0x3E, 0x41, 0x51, 0x21, 0x5E],
+    [0x7F, 0x09, 0x19, 0x29, 0x46],
+    [0x46, 0x49, 0x49, 0x49, 0x31],
+    [0x01, 0x01, 0x7F, 0x01, 0x01],
+    [0x3F, 0x40, 0x40, 0x40, 0x3F],
+    [0x1F, 0x20, 0x40, 0x20, 0x1F],
+    [0x3F, 0x40, 0x38, 0x40, 0x3F],
+    [0x63, 0x14, 0x08, 0x14, 0x63],
+    [0x07, 0x08, 0x70, 0x08, 0x07],
+    [0x61, 0x51, 0x49, 0x45, 0x43],
+    [0x00, 0x7F, 0x41, 0x41, 0x00],
+    [0x02, 0x04, 0x08, 0x10, 0x20],
+    [0x00, 0x41, 0x41, 0x7F, 0x00],
+    [0x04, 0x02, 0x01, 0x02, 0x04],
+    [0x40, 0x40, 0x40, 0x40, 0x40],
+    [0x00, 0x01, 0x02, 0x04, 0x00],
+    [0x20, 0x54, 0x54, 0x54, 0x78],
+    [0x7F, 0x48, 0x44, 0x44, 0x38],
+    [0x38, 0x44, 0x44, 0x44, 0x20],
+    [0x38, 0x44, 0x44, 0x48, 0x7F],
+    [0x38, 0x54, 0x54, 0x54, 0x18],
+    [0x08, 0x7E, 0x09, 0x01, 0x02],
+    [0x0C, 0x52, 0x52, 0x52, 0x3E],
+    [0x7F, 0x08, 0x04, 0x04, 0x78],
+    [0x00, 0x44, 0x7D, 0x40, 0x00],
+    [0x20, 0x40, 0x44, 0x3D, 0x00],
+    [0x7F, 0x10, 0x28, 0x44, 0x00],
+    [0x00, 0x41, 0x7F, 0x40, 0x00],
+    [0x7C, 0x04, 0x18, 0x04, 0x78],
+    [0x7C, 0x08, 0x04, 0x04, 0x78],
+    [0x38, 0x44, 0x44, 0x44, 0x38],
+    [0x7C, 0x14, 0x14, 0x14, 0x08],
+    [0x08, 0x14, 0x14, 0x18, 0x7C],
+    [0x7C, 0x08, 0x04, 0x04, 0x08],
+    [0x48, 0x54, 0x54, 0x54, 0x20],
+    [0x04, 0x3F, 0x44, 0x40, 0x20],
+    [0x3C, 0x40, 0x40, 0x20, 0x7C],
+    [0x1C, 0x20, 0x40, 0x20, 0x1C],
+    [0x3C, 0x40, 0x30, 0x40, 0x3C],
+    [0x44, 0x28, 0x10, 0x28, 0x44],
+    [0x0C, 0x50, 0x50, 0x50, 0x3C],
+    [0x44, 0x64, 0x54, 0x4C, 0x44],
+    [0x00, 0x08, 0x36, 0x41, 0x00],
+    [0x00, 0x00, 0x7F, 0x00, 0x00],
+    [0x00, 0x41, 0x36, 0x08, 0x00],
+    [0x08, 0x04, 0x08, 0x10, 0x08],
+];
+
+/**
+Which side of a Node or Group its label is drawn on.
+ */
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Placement {
+    #[default]
+    Right,
+    Left,
+    Above,
+    Below,
+}
+
+impl Placement {
+    /**
+    Returns the placements to try, starting with self.
+     */
+    fn order(self) -> [Placement; 4] {
+        match self {
+            Placement::Right => [
+                Placement::Right,
+                Placement::Above,
+                Placement::Below,
+                Placement::Left,
+            ],
+            Placement::Left => [
+                Placement::Left,
+                Placement::Above,
+                Placement::Below,
+                Placement::Right,
+            ],
+            Placement::Above => [
+                Placement::Above,
+                Placement::Right,
+                Placement::Left,
+                Placement::Below,
+            ],
+            Placement::Below => [
+                Placement::Below,
+                Placement::Right,
+                Placement::Left,
+                Placement::Above,
+            ],
+        }
+    }
+
+    /**
+    Returns the top left corner of a label with the size next to the area between min and max.
+     */
+    fn position(self, min: Coordinate, max: Coordinate, size: Coordinate) -> Coordinate {
+        let center = coordinate!((min.x + max.x) / 2, (min.y + max.y) / 2);
+        match self {
+            Placement::Right => coordinate!(max.x + MARGIN, center.y - size.y / 2),
+            Placement::Left => coordinate!(min.x - MARGIN - size.x, center.y - size.y / 2),
+            Placement::Above => coordinate!(center.x - size.x / 2, min.y - MARGIN - size.y),
+            Placement::Below => coordinate!(center.x - size.x / 2, max.y + MARGIN),
+        }
+    }
+}
+
+/**
+Keeps track of the labels drawn on a canvas, to place new ones where they do not overlap.
+ */
+#[derive(Clone, Debug, Default)]
+pub struct Labels {
+    placement: Placement,
+    taken: Vec<(Coordinate, Coordinate)>,
+}
+
+impl Labels {
+    /**
+    Constructs an empty set of labels which prefers the given placement.
+     */
+    pub fn new(placement: Placement) -> Self {
+        Labels {
+            placement,
+            taken: Vec::new(),
+        }
+    }
+
+    /**
+    Finds a free position for a label of the size next to the area between min and max, and reserves it.
+
+    Positions inside the canvas are preferred. Returns None if every side overlaps another label.
+
+
+    ## Examples
+
+    ```
+    # use pathtracer::{map::label::*, *};
+    let mut labels = Labels::new(Placement::Right);
+    let canvas = Coordinate::new(100, 100);
+    let size = Coordinate::new(20, 7);
+    let right = labels.place(Coordinate::new(10, 10), Coordinate::new(14, 14), size, canvas);
+    assert_eq!(right, Some(Coordinate::new(16, 9)));
+    // The right side is taken, so the label goes above instead.
+    let above = labels.place(Coordinate::new(10, 10), Coordinate::new(14, 14), size, canvas);
+    assert_eq!(above, Some(Coordinate::new(2, 1)));
+    ```
+     */
+    pub fn place(
+        &mut self,
+        min: Coordinate,
+        max: Coordinate,
+        size: Coordinate,
+        canvas: Coordinate,
+    ) -> Option<Coordinate> {
+        let free = self
+            .placement
+            .order()
+            .iter()
+            .map(|p| p.position(min, max, size))
+            .filter(|&pos| !self.taken.iter().any(|&t| overlaps((pos, pos + size), t)))
+            .collect::<Vec<_>>();
+        let inside = |pos: &&Coordinate| {
+            pos.x >= 0 && pos.y >= 0 && pos.x + size.x <= canvas.x && pos.y + size.y <= canvas.y
+        };
+        let pos = *free.iter().find(inside).or_else(|| free.first())?;
+        self.taken.push((pos, pos + size));
+        Some(pos)
+    }
+}
+
+/**
+Returns true if the two areas share any pixels, each given as the top left and bottom right corner.
+ */
+fn overlaps(a: (Coordinate, Coordinate), b: (Coordinate, Coordinate)) -> bool {
+    a.0.x < b.1.x && b.0.x < a.1.x && a.0.y < b.1.y && b.0.y < a.1.y
+}
+
+/**
+Returns the width and height of the text in pixels, when drawn using text.
+
+
+## Examples
+
+```
+# use pathtracer::{map::label, *};
+assert_eq!(label::size("Node"), Coordinate::new(23, 7));
+assert_eq!(label::size(""), Coordinate::new(0, 7));
+```
+ */
+pub fn size(text: &str) -> Coordinate {
+    let count = text.chars().count() as i32;
+    coordinate!(
+        std::cmp::max(count * (GLYPH_WIDTH + 1) - 1, 0),
+        GLYPH_HEIGHT
+    )
+}
+
+/**
+Draws the text with the top left corner at the position.
+
+
+## Examples
+
+```
+# use pathtracer::{map::label, *};
+let mut image = Map::new().size(30, 10).map::<Node>(&[]).consume();
+label::text(&mut image, Coordinate::new(1, 1), "Hi!", image::Rgba([255, 255, 255, 255]));
+// The left side of the H.
+assert_eq!(image.image().get_pixel(1, 4).0, [255, 255, 255, 255]);
+assert_eq!(image.image().get_pixel(2, 1).0, [0, 0, 0, 255]);
+```
+ */
+pub fn text(image: &mut IW, pos: Coordinate, text: &str, color: Rgba<u8>) {
+    for (i, c) in text.chars().enumerate() {
+        let x = pos.x + i as i32 * (GLYPH_WIDTH + 1);
+        for (column, bits) in glyph(c).iter().enumerate() {
+            for row in 0..GLYPH_HEIGHT {
+                if bits & (1 << row) != 0 {
+                    image.put(&coordinate!(x + column as i32, pos.y + row), color);
+                }
+            }
+        }
+    }
+}
+
+/**
+Returns the columns of the character, or of '?' if it is not in the font.
+ */
+fn glyph(c: char) -> [u8; 5] {
+    let index = match c {
+        ' '..='~' => c as usize - ' ' as usize,
+        _ => '?' as usize - ' ' as usize,
+    };
+    GLYPHS[index]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn glyphs() {
+        assert_eq!(glyph(' '), [0; 5]);
+        assert_eq!(glyph('A'), [0x7E, 0x11, 0x11, 0x11, 0x7E]);
+        assert_eq!(glyph('~'), [0x08, 0x04, 0x08, 0x10, 0x08]);
+        assert_eq!(glyph('é'), glyph('?'));
+    }
+
+    #[test]
+    fn overlapping() {
+        let a = (coordinate!(0, 0), coordinate!(10, 10));
+        assert!(overlaps(a, (coordinate!(5, 5), coordinate!(15, 15))));
+        assert!(!overlaps(a, (coordinate!(10, 0), coordinate!(20, 10))));
+        assert!(!overlaps(a, (coordinate!(0, 11), coordinate!(10, 20))));
+    }
+
+    #[test]
+    fn prefers_inside_canvas() {
+        let mut labels = Labels::new(Placement::Left);
+        let pos = labels.place(
+            coordinate!(0, 20),
+            coordinate!(4, 24),
+            coordinate!(20, 7),
+            coordinate!(100, 100),
+        );
+        // Only the right side is inside the canvas.
+        assert_eq!(pos, Some(coordinate!(6, 19)));
+    }
+
+    #[test]
+    fn gives_up_when_crowded() {
+        let mut labels = Labels::new(Placement::Right);
+        let (min, max) = (coordinate!(50, 50), coordinate!(54, 54));
+        let size = coordinate!(10, 7);
+        let canvas = coordinate!(100, 100);
+        for _ in 0..4 {
+            assert!(labels.place(min, max, size, canvas).is_some());
+        }
+        assert_eq!(labels.place(min, max, size, canvas), None);
+    }
+}
@@ -5,23 +5,191 @@ Functions to manage large numbers of placable entities.
 extern crate image;
 
 use super::*;
-use image::Rgb;
+use image::Rgba;
 use std::cmp;
 
+pub mod composite;
+pub mod format;
 pub mod gif;
+pub mod label;
 pub mod network;
+pub mod raster;
+pub mod route;
+pub mod svg;
+pub mod theme;
+
+/**
+Maps world positions on to the pixels of a canvas.
+
+The origin is the world position drawn at the top left pixel, and the zoom is the number of pixels per world unit.
+Only positions are scaled, Nodes and links keep their size in pixels.
+
+The constructors clamp the zoom to at least MIN_ZOOM, as a zoom of zero or less has no pixels to draw on.
+ */
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Viewport {
+    pub origin: Point,
+    pub zoom: f64,
+}
+
+/**
+The lowest zoom of a Viewport. Lower, negative and NaN zooms are raised to it.
+ */
+pub const MIN_ZOOM: f64 = 1e-6;
+
+impl Default for Viewport {
+    fn default() -> Self {
+        Viewport::new(Point::default(), 1.0)
+    }
+}
+
+impl Viewport {
+    /**
+    Constructs a Viewport with the origin at the top left pixel.
+     */
+    pub fn new(origin: Point, zoom: f64) -> Self {
+        Viewport {
+            origin,
+            zoom: zoom.max(MIN_ZOOM),
+        }
+    }
+
+    /**
+    Constructs a Viewport with the center in the middle of a canvas with the given size.
+
+
+    ## Examples
+
+    ```
+    # use pathtracer::{map::Viewport, *};
+    let viewport = Viewport::centered(Point::new(100.0, 100.0), 2.0, 200, 100);
+    assert_eq!(viewport.project(Point::new(100.0, 100.0)), Coordinate::new(100, 50));
+    assert_eq!(viewport.project(Point::new(50.0, 75.0)), Coordinate::new(0, 0));
+    ```
+     */
+    pub fn centered(center: Point, zoom: f64, width: u32, height: u32) -> Self {
+        let zoom = zoom.max(MIN_ZOOM);
+        let half = Point::new(
+            f64::from(width) / 2.0 / zoom,
+            f64::from(height) / 2.0 / zoom,
+        );
+        Viewport::new(center - half, zoom)
+    }
+
+    /**
+    Returns the pixel the world position is drawn at.
+     */
+    pub fn project(&self, p: Point) -> Coordinate {
+        let p = p - self.origin;
+        Point::new(p.x * self.zoom, p.y * self.zoom).position()
+    }
+}
+
+/**
+An owned copy of an element, which a Map fitting all layers keeps to draw it again.
+ */
+#[derive(Clone, Debug)]
+pub enum Snapshot {
+    Node(Node),
+    Group(Box<Group>),
+}
+
+impl Draw for Snapshot {
+    fn draw(&self, image: IW, offset: Coordinate, shape: &Shape) -> IW {
+        match self {
+            Snapshot::Node(node) => node.draw(image, offset, shape),
+            Snapshot::Group(group) => group.draw(image, offset, shape),
+        }
+    }
+
+    fn size(&self) -> u32 {
+        match self {
+            Snapshot::Node(node) => node.size(),
+            Snapshot::Group(group) => group.size(),
+        }
+    }
+
+    fn links(&self) -> &[HL] {
+        match self {
+            Snapshot::Node(node) => node.links(),
+            Snapshot::Group(group) => group.links(),
+        }
+    }
+
+    fn draw_svg(&self, svg: &mut svg::Svg, offset: Coordinate, shape: &Shape) {
+        match self {
+            Snapshot::Node(node) => node.draw_svg(svg, offset, shape),
+            Snapshot::Group(group) => group.draw_svg(svg, offset, shape),
+        }
+    }
+
+    fn snapshot(&self) -> Option<Snapshot> {
+        Some(self.clone())
+    }
+}
+
+impl Location for Snapshot {
+    fn position(&self) -> Coordinate {
+        match self {
+            Snapshot::Node(node) => node.position(),
+            Snapshot::Group(group) => group.position(),
+        }
+    }
+}
+
+impl Hash for Snapshot {
+    fn hash(&self) -> u64 {
+        match self {
+            Snapshot::Node(node) => node.hash(),
+            Snapshot::Group(group) => group.hash(),
+        }
+    }
+}
+
+impl MinMax for Snapshot {
+    fn min_max(&self) -> (Coordinate, Coordinate) {
+        match self {
+            Snapshot::Node(node) => node.min_max(),
+            Snapshot::Group(group) => group.min_max(),
+        }
+    }
+}
+
+/**
+Elements kept by a Map, with the shape they are drawn as, so they can be drawn again once the bounds of every layer are known.
+ */
+pub(crate) type Layer = (Vec<Snapshot>, Shape);
+
+/**
+Returns the bounds of the elements in the layer, None if it has none.
+ */
+pub(crate) fn layer_min_max(layer: &Layer) -> Option<(Coordinate, Coordinate)> {
+    layer.0.iter().map(|e| e.min_max()).reduce(|a, b| {
+        (
+            coordinate!(cmp::min(a.0.x, b.0.x), cmp::min(a.0.y, b.0.y)),
+            coordinate!(cmp::max(a.1.x, b.1.x), cmp::max(a.1.y, b.1.y)),
+        )
+    })
+}
 
 /**
 Returns the underlaying image used for the Map struct.
 */
 pub fn gen_map<T: Location + Draw + MinMax>(
     list: &[T],
-) -> (image::ImageBuffer<Rgb<u8>, Vec<u8>>, Coordinate) {
+) -> (image::ImageBuffer<Rgba<u8>, Vec<u8>>, Coordinate) {
+    let (size, add) = bounds(list);
+    let image = gen_canvas(size.x as u32, size.y as u32, consts::DEFAULT_RGBA);
+    (image, add)
+}
+
+/**
+Returns the size of the canvas the list fits on, and the offset which moves the list on to it.
+*/
+pub fn bounds<T: Location + Draw + MinMax>(list: &[T]) -> (Coordinate, Coordinate) {
     let (min, max) = min_max(list);
-    let diff = max - min;
     let add = Coordinate::new(-min.x, -min.y);
-    let image = gen_canvas(diff.x as u32, diff.y as u32);
-    (image, add)
+    (max - min, add)
 }
 
 /**
@@ -30,12 +198,12 @@ Finds the min and max of a list and returns (min, max).
 the min and max use the size of the Draw trait to enlarge the are the min, max occupy.
 */
 fn min_max<T: Location + Draw + MinMax>(list: &[T]) -> (Coordinate, Coordinate) {
-    let mut size: i16 = consts::DEFAULT_SIZE as i16;
+    let mut size: i32 = consts::DEFAULT_SIZE as i32;
     let mut min = coordinate!();
     let mut max = coordinate!();
 
     for item in list {
-        size = cmp::max(size, item.size() as i16);
+        size = cmp::max(size, item.size() as i32);
         let (imin, imax) = item.min_max();
 
         max.x = cmp::max(max.x, imax.x);
@@ -49,10 +217,14 @@ fn min_max<T: Location + Draw + MinMax>(list: &[T]) -> (Coordinate, Coordinate)
 }
 
 /**
-Generates a canvas from the image crate.
+Generates a canvas filled with the background color from the image crate.
 */
-fn gen_canvas(w: u32, h: u32) -> image::ImageBuffer<image::Rgb<u8>, Vec<u8>> {
-    image::DynamicImage::new_rgb8(w, h).to_rgb8()
+pub(crate) fn gen_canvas(
+    w: u32,
+    h: u32,
+    background: Rgba<u8>,
+) -> image::ImageBuffer<Rgba<u8>, Vec<u8>> {
+    image::ImageBuffer::from_pixel(w, h, background)
 }
 
 #[cfg(test)]
@@ -60,18 +232,170 @@ mod tests {
     use super::*;
     #[test]
     fn test_gen_canvas() {
-        let image = gen_canvas(50, 50);
+        let image = gen_canvas(50, 50, consts::DEFAULT_RGBA);
         assert_eq!(image.width(), 50);
         assert_eq!(image.height(), 50);
     }
 
     #[test]
     fn test_gen_canvas_2() {
-        let image = gen_canvas(0, 0);
+        let image = gen_canvas(0, 0, consts::DEFAULT_RGBA);
         assert_eq!(image.width(), 0);
         assert_eq!(image.height(), 0);
     }
 
+    fn red(list: &[(i32, i32)]) -> Vec<Node> {
+        let mut nodes = Node::from_list(list);
+        for node in nodes.iter_mut() {
+            node.color = Some(Rgba([255, 0, 0, 255]));
+        }
+        nodes
+    }
+
+    fn is_red(image: &IW, c: Coordinate) -> bool {
+        image.image().get_pixel(c.x as u32, c.y as u32).0 == [255, 0, 0, 255]
+    }
+
+    #[test]
+    fn test_viewport() {
+        let viewport = Viewport::new(Point::new(-10.0, 20.0), 0.5);
+        assert_eq!(viewport.project(Point::new(-10.0, 20.0)), coordinate!());
+        assert_eq!(
+            viewport.project(Point::new(90.0, 40.0)),
+            coordinate!(50, 10)
+        );
+        let centered = Viewport::centered(Point::new(0.0, 0.0), 1.0, 100, 50);
+        assert_eq!(centered.origin, Point::new(-50.0, -25.0));
+    }
+
+    #[test]
+    fn test_viewport_clamps_zoom() {
+        for zoom in [0.0, -2.0, f64::NAN] {
+            assert_eq!(Viewport::new(Point::default(), zoom).zoom, MIN_ZOOM);
+            let centered = Viewport::centered(Point::default(), zoom, 100, 50);
+            assert_eq!(centered.zoom, MIN_ZOOM);
+            assert!(centered.origin.x.is_finite() && centered.origin.y.is_finite());
+            assert_eq!(centered.project(Point::default()), coordinate!(50, 25));
+        }
+
+        let mut node = Node::new("A", coordinate!(10, 10));
+        node.color = Some(Rgba([255, 0, 0, 255]));
+        let image = Map::new()
+            .size(100, 100)
+            .viewport(Point::default(), 0.0)
+            .map(&[node])
+            .consume();
+        assert!(is_red(&image, coordinate!(50)));
+    }
+
+    #[test]
+    fn test_fixed_size_clips() {
+        let nodes = red(&[(-50, -50), (5000, 5000), (10, 10)]);
+        let image = Map::new().size(100, 100).map(&nodes).consume();
+        assert_eq!(image.dimensions(), coordinate!(100, 100));
+        assert!(is_red(&image, coordinate!(10)));
+    }
+
+    #[test]
+    fn test_padding() {
+        let nodes = red(&[(0, 0), (100, 100)]);
+        let plain = Map::new().map(&nodes).consume();
+        let padded = Map::new().padding(10).map(&nodes).consume();
+        assert_eq!(padded.dimensions(), plain.dimensions() + coordinate!(20));
+        assert!(is_red(&padded, coordinate!(115)));
+    }
+
+    #[test]
+    fn test_fit_all_layers() {
+        let a = red(&[(0, 0)]);
+        let b = red(&[(400, -300)]);
+        let image = Map::new().fit().map(&a).map(&b).consume();
+        assert_eq!(image.dimensions(), coordinate!(408, 308));
+        assert!(is_red(&image, coordinate!(4, 305)));
+        assert!(is_red(&image, coordinate!(404, 5)));
+    }
+
+    // An element borrowing the Node it draws, which has no Snapshot.
+    struct Borrowed<'a>(&'a Node);
+
+    impl Draw for Borrowed<'_> {
+        fn draw(&self, image: IW, offset: Coordinate, shape: &Shape) -> IW {
+            self.0.draw(image, offset, shape)
+        }
+
+        fn size(&self) -> u32 {
+            self.0.size()
+        }
+
+        fn links(&self) -> &[HL] {
+            self.0.links()
+        }
+    }
+
+    impl Location for Borrowed<'_> {
+        fn position(&self) -> Coordinate {
+            self.0.position()
+        }
+    }
+
+    impl Hash for Borrowed<'_> {
+        fn hash(&self) -> u64 {
+            self.0.hash()
+        }
+    }
+
+    impl MinMax for Borrowed<'_> {
+        fn min_max(&self) -> (Coordinate, Coordinate) {
+            self.0.min_max()
+        }
+    }
+
+    #[test]
+    fn test_map_borrowed() {
+        let nodes = red(&[(0, 0), (20, 20)]);
+        let borrowed = nodes.iter().map(Borrowed).collect::<Vec<_>>();
+        let image = Map::new().map(&borrowed).consume();
+        let owned = Map::new().map(&nodes).consume();
+        assert_eq!(image.image().as_raw(), owned.image().as_raw());
+
+        // Without a Snapshot, the elements are drawn on the fitted canvas as it is.
+        let mut near = red(&[(10, 10)]);
+        near[0].color = Some(Rgba([0, 0, 255, 255]));
+        let borrowed = near.iter().map(Borrowed).collect::<Vec<_>>();
+        let image = Map::new().fit().map(&nodes).map(&borrowed).consume();
+        let owned = Map::new().fit().map(&nodes).map(&near).consume();
+        assert_eq!(image.dimensions(), coordinate!(28, 28));
+        assert_eq!(image.image().as_raw(), owned.image().as_raw());
+        let mut groups = vec![Group::new("G", coordinate!(-30, 0))];
+        groups[0].push(node!(0, 0));
+        let image = Map::new().fit().map(&nodes).map(&groups).consume();
+        assert!(image.dimensions().x > 28);
+    }
+
+    #[test]
+    fn test_fit_zooms_to_size() {
+        let nodes = red(&[(0, 0), (1000, 1000)]);
+        let image = Map::new()
+            .size(110, 110)
+            .padding(5)
+            .fit()
+            .map(&nodes)
+            .consume();
+        assert_eq!(image.dimensions(), coordinate!(110, 110));
+        assert!(is_red(&image, coordinate!(7)));
+        assert!(is_red(&image, coordinate!(106)));
+    }
+
+    #[test]
+    fn test_fit_svg() {
+        let a = Node::from_list(&[(0, 0)]);
+        let b = Node::from_list(&[(100, 50)]);
+        let map = Map::new_svg().fit().map(&a).map(&b);
+        let svg = map.svg().unwrap();
+        assert_eq!(svg.dimensions(), coordinate!(108, 58));
+        assert_eq!(svg.to_string().matches("<rect").count(), 3);
+    }
+
     #[test]
     fn test_min_max() {
         let nodes = Node::from_list(&[(-50, 50), (50, -50), (0, 25), (25, 0)]);
@@ -80,6 +404,14 @@ mod tests {
         assert_eq!(max, Coordinate::new(55, 55));
     }
 
+    #[test]
+    fn test_bounds_beyond_i16() {
+        let nodes = Node::from_list(&[(40_000, -40_000), (40_100, -39_900)]);
+        let (size, add) = bounds(&nodes);
+        assert_eq!(size, Coordinate::new(40_106, 40_006));
+        assert_eq!(add, Coordinate::new(1, 40_005));
+    }
+
     #[test]
     fn test_min_max_2() {
         let nodes = Node::from_list(&[(-9999, 50), (50, -50), (0, 25), (9999, 0)]);
@@ -87,4 +419,241 @@ mod tests {
         assert_eq!(min, Coordinate::new(-10004, -55));
         assert_eq!(max, Coordinate::new(10004, 55));
     }
+
+    #[test]
+    fn test_translucent_nodes_are_not_hidden() {
+        let mut nodes = red(&[(0, 0), (2, 2)]);
+        nodes[1].color = Some(Rgba([0, 0, 255, 128]));
+        let image = Map::new().size(20, 20).map(&nodes).consume();
+        let pixel = image.image().get_pixel(2, 2).0;
+        assert!(pixel[0] > 100 && pixel[2] > 100, "{:?}", pixel);
+        assert_eq!(pixel[3], 255);
+    }
+
+    #[test]
+    fn test_save_without_alpha() {
+        let map = Map::new().map(&red(&[(0, 0), (10, 10)]));
+        map.clone()
+            .save(std::path::Path::new("/tmp/test_rgba.png"))
+            .unwrap();
+        map.save(std::path::Path::new("/tmp/test_rgba.jpg"))
+            .unwrap();
+        let jpeg = image::open("/tmp/test_rgba.jpg").unwrap();
+        assert!(!jpeg.color().has_alpha());
+    }
+
+    #[test]
+    fn test_theme() {
+        let nodes = Node::linked_list(Node::from_list(&[(0, 10), (40, 10)]));
+        let edge = Rgba([0, 0, 255, 255]);
+        let theme = theme::Theme {
+            node: Rgba([0, 255, 0, 255]),
+            edge: Some(edge.into()),
+            ..theme::Theme::light()
+        };
+        let image = Map::new().size(60, 30).theme(theme).map(&nodes).consume();
+        assert_eq!(image.image().get_pixel(59, 29).0, [255, 255, 255, 255]);
+        assert_eq!(image.image().get_pixel(20, 10).0, edge.0);
+        assert_eq!(image.image().get_pixel(1, 11).0, [0, 255, 0, 255]);
+    }
+
+    #[test]
+    fn test_large_shade() {
+        let nodes = Node::linked_list(Node::from_list(&[(0, 10), (40, 10)]));
+        let theme = theme::Theme {
+            shade: 200,
+            ..theme::Theme::default()
+        };
+        let image = Map::new().size(60, 30).theme(theme).map(&nodes).consume();
+        let pixel = image.image().get_pixel(20, 10).0;
+        assert!(pixel[0] >= 200, "{:?}", pixel);
+    }
+
+    #[test]
+    fn test_wide_highlight() {
+        let mut nodes = Node::linked_list(Node::from_list(&[(0, 20), (60, 20)]));
+        nodes[1].hl_mut(0).unwrap().width(14);
+        for rasterizer in [raster::Rasterizer::Aliased, raster::Rasterizer::AntiAliased].iter() {
+            let image = Map::new()
+                .size(80, 40)
+                .rasterizer(*rasterizer)
+                .map(&nodes)
+                .consume();
+            assert_ne!(image.image().get_pixel(30, 15).0, consts::DEFAULT_RGBA.0);
+        }
+    }
+
+    #[test]
+    fn test_labels() {
+        let mut nodes = Node::from_list(&[(10, 10), (10, 10)]);
+        nodes[0].label("A");
+        nodes[1].label("B");
+        let white = |image: &IW, x: std::ops::Range<u32>, y: std::ops::Range<u32>| {
+            x.flat_map(|x| y.clone().map(move |y| (x, y)))
+                .filter(|&(x, y)| image.image().get_pixel(x, y).0 == [255, 255, 255, 255])
+                .count()
+        };
+        for rasterizer in [raster::Rasterizer::Aliased, raster::Rasterizer::AntiAliased].iter() {
+            let image = Map::new()
+                .size(40, 40)
+                .rasterizer(*rasterizer)
+                .map(&nodes)
+                .consume();
+            // The second label collides with the first, so it goes above the node.
+            assert!(white(&image, 14..30, 0..40) > 0);
+            assert!(white(&image, 0..14, 0..10) > 0);
+        }
+
+        let mut group = Group::new("G", Coordinate::new(20, 20));
+        group.label("G");
+        let image = Map::new().size(60, 40).map(&[group]).consume();
+        assert!(white(&image, 0..60, 0..40) > 0);
+    }
+
+    #[test]
+    fn test_arrowheads() {
+        let line = Coordinate::from_list(&[(0, 0), (10, 0), (10, 0)]);
+        let [left, tip, right] = HL::arrowhead(&line, 2, 4).unwrap();
+        assert_eq!(tip, Point::new(6.0, 0.0));
+        assert_eq!(left, Point::new(-4.0, 5.0));
+        assert_eq!(right, Point::new(-4.0, -5.0));
+        assert_eq!(HL::arrowhead(&[coordinate!(3)], 2, 4), None);
+
+        // Curves point along the last segment rather than straight from the start.
+        let mut hl = HL::new(1, 2);
+        hl.style(EdgeStyle::Ellipse);
+        let line = hl.polyline(coordinate!(0, 0), coordinate!(100, 60), &[]);
+        let [left, tip, right] = HL::arrowhead(&line, 2, 4).unwrap();
+        let end = Point::from(line[line.len() - 1]) - Point::from(line[line.len() - 2]);
+        let base = Point::new((left.x + right.x) / 2.0, (left.y + right.y) / 2.0);
+        let arrow = tip - base;
+        assert!((arrow.x * end.y - arrow.y * end.x).abs() < 1e-6);
+        assert!(arrow.x * end.x + arrow.y * end.y > 0.0);
+
+        let mut nodes = Node::linked_list(Node::from_list(&[(0, 0), (40, 40)]));
+        nodes[1].hl_mut(0).unwrap().arrow(Arrow::Filled);
+        let svg = Map::new_svg().map(&nodes).svg().unwrap().to_string();
+        assert_eq!(svg.matches("<polygon").count(), 1);
+        nodes[1].hl_mut(0).unwrap().arrow(Arrow::Open);
+        let svg = Map::new_svg().map(&nodes).svg().unwrap().to_string();
+        assert_eq!(svg.matches("<polygon").count(), 0);
+        assert_eq!(svg.matches("<path").count(), 1);
+
+        // The tip stops at the edge of the Node it points at.
+        let tip = |radius| {
+            let mut nodes = Node::linked_list(Node::from_list(&[(0, 0), (100, 0)]));
+            nodes[0].radius = radius;
+            nodes[1].hl_mut(0).unwrap().arrow(Arrow::Filled);
+            let svg = Map::new_svg().size(140, 40).map(&nodes);
+            let svg = svg.svg().unwrap().to_string();
+            let points = svg
+                .split("points=\"")
+                .nth(1)
+                .unwrap()
+                .split('"')
+                .next()
+                .unwrap();
+            let tip = points.split(' ').nth(1).unwrap();
+            tip.split(',').next().unwrap().parse::<i32>().unwrap()
+        };
+        assert_eq!(tip(Some(20)) - tip(None), 16);
+    }
+
+    #[test]
+    fn test_edge_styles() {
+        let blue = Rgba([0, 0, 255, 255]);
+        let is_blue =
+            |image: &IW, x: i32, y: i32| image.image().get_pixel(x as u32, y as u32).0 == blue.0;
+        for rasterizer in [raster::Rasterizer::Aliased, raster::Rasterizer::AntiAliased].iter() {
+            let map = || {
+                Map::new()
+                    .size(120, 60)
+                    .padding(20)
+                    .rasterizer(*rasterizer)
+                    .theme(theme::Theme {
+                        edge: Some(blue.into()),
+                        ..theme::Theme::default()
+                    })
+            };
+
+            // Routed around the Node in the middle.
+            let mut nodes = red(&[(0, 0), (40, 0), (80, 0)]);
+            let first = nodes[0].clone();
+            nodes[2].link(&first);
+            nodes[2].hl_mut(0).unwrap().style(EdgeStyle::Orthogonal);
+            let image = map().map(&nodes).consume();
+            assert!((0..60).any(|y| (y - 20i32).abs() > 4 && is_blue(&image, 60, y)));
+            assert!(!is_blue(&image, 50, 20), "{:?}", rasterizer);
+
+            // Bowed to the left of the direction, and above the Nodes when going right.
+            let mut nodes = red(&[(0, 0), (80, 0)]);
+            let last = nodes[1].clone();
+            nodes[0].link(&last);
+            nodes[0].hl_mut(0).unwrap().style(EdgeStyle::Bezier(25));
+            let image = map().map(&nodes).consume();
+            assert!((0..20).any(|y| is_blue(&image, 60, y)), "{:?}", rasterizer);
+            assert!(!is_blue(&image, 60, 20), "{:?}", rasterizer);
+
+            // Gaps are left between the dashes.
+            nodes[0].hl_mut(0).unwrap().style(EdgeStyle::Direct);
+            nodes[0].hl_mut(0).unwrap().stroke(Stroke::Dashed);
+            let image = map().map(&nodes).consume();
+            let row = (30..90).map(|x| is_blue(&image, x, 20)).collect::<Vec<_>>();
+            assert!(
+                row.iter().any(|&b| b) && row.iter().any(|&b| !b),
+                "{:?}",
+                rasterizer
+            );
+        }
+    }
+
+    #[test]
+    fn test_shapes() {
+        let area = |shape: Shape| shape.area(10);
+        for shape in [Shape::Diamond, Shape::Hexagon, Shape::Star] {
+            let pixels = area(shape.clone());
+            assert!(!pixels.is_empty(), "{:?}", shape);
+            assert!(pixels
+                .iter()
+                .all(|c| (0..10).contains(&c.x) && (0..10).contains(&c.y)));
+        }
+        assert!(area(Shape::Star).len() < area(Shape::Hexagon).len());
+        assert!(area(Shape::Diamond).len() < area(Shape::Square).len());
+        let ring = area(Shape::Ring);
+        assert!(!ring.contains(&coordinate!(0, 0)));
+        assert!(ring.contains(&coordinate!(9, 0)));
+
+        // The Node's own shape is used over the one given to the Map.
+        let mut nodes = red(&[(10, 10), (30, 10)]);
+        nodes[0].shape(Shape::Ring);
+        for rasterizer in [raster::Rasterizer::Aliased, raster::Rasterizer::AntiAliased].iter() {
+            let image = Map::new()
+                .size(40, 20)
+                .rasterizer(*rasterizer)
+                .map_shape(&nodes, &Shape::Square)
+                .consume();
+            assert!(!is_red(&image, coordinate!(9)), "{:?}", rasterizer);
+            assert!(is_red(&image, coordinate!(31, 11)), "{:?}", rasterizer);
+        }
+    }
+
+    #[test]
+    fn test_link_paint_and_width() {
+        let mut nodes = Node::linked_list(Node::from_list(&[(0, 10), (40, 10)]));
+        nodes[1]
+            .hl_mut(0)
+            .unwrap()
+            .paint(Rgba([255, 0, 0, 255]).into());
+        nodes[1].hl_mut(0).unwrap().width(4);
+        for rasterizer in [raster::Rasterizer::Aliased, raster::Rasterizer::AntiAliased].iter() {
+            let image = Map::new()
+                .size(60, 30)
+                .rasterizer(*rasterizer)
+                .map(&nodes)
+                .consume();
+            assert!(is_red(&image, coordinate!(20, 9)), "{:?}", rasterizer);
+            assert!(is_red(&image, coordinate!(20, 10)), "{:?}", rasterizer);
+            assert!(!is_red(&image, coordinate!(20, 14)), "{:?}", rasterizer);
+        }
+    }
 }
@@ -3,7 +3,10 @@ Connects and paths between different connected Nodes.
  */
 
 use super::*;
-use std::io::{self, Error, ErrorKind};
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap, HashSet},
+};
 
 /**
 Weighted Node
@@ -19,27 +22,24 @@ Paths between two different points that are connected.
 
 ## Errors
 
-The provided A and B don't exist in the network.
+Error::NodeNotFound if the provided A or B don't exist in the network.
 
-The path could not be found.
+Error::NoPath if the path could not be found.
  */
+#[allow(clippy::type_complexity)]
 pub fn path<'a>(
     network: &'a Network<Node>,
     a: &str,
     b: &str,
-    algorithm: &dyn Fn(&Network<Node>, Node, Node) -> io::Result<Vec<Node>>,
-) -> io::Result<Vec<Node>> {
-    let opt_start = network.get(a);
-    if let Some(start) = opt_start {
-        let opt_goal = network.get(b);
-        if let Some(goal) = opt_goal {
-            return algorithm(network, start, goal);
-        }
-    }
-    Err(Error::new(
-        ErrorKind::Other,
-        "Start or Goal path does not exist in Network",
-    ))
+    algorithm: &dyn Fn(&Network<Node>, Node, Node) -> Result<Vec<Node>, Error>,
+) -> Result<Vec<Node>, Error> {
+    let start = network
+        .get(a)
+        .ok_or_else(|| Error::NodeNotFound(a.to_string()))?;
+    let goal = network
+        .get(b)
+        .ok_or_else(|| Error::NodeNotFound(b.to_string()))?;
+    algorithm(network, start, goal)
 }
 
 /**
@@ -47,13 +47,222 @@ Retrieves a node from a network.
  */
 pub fn get(network: &Network<Node>, element: &str) -> Option<Node> {
     let hash = node!(element, 0, 0).hash;
-    for (i, elem) in network.hash_map.iter().enumerate() {
-        if elem.is_some() && i == hash as usize % consts::NETWORK_REM {
-            return network.hash_map[i];
+    network.lookup(hash).cloned()
+}
+
+/**
+The default cost of traversing a link.
+
+Uses the weight of the HL when it is set, otherwise the distance between the two Nodes.
+
+
+## Examples
+
+```
+# #[macro_use] use pathtracer::{map::network, *};
+# fn main() {
+let b = node!("B", 30, 40);
+let mut a = node!("A", 0, 0);
+a.link(&b);
+a.link_weighted(&b, 7);
+assert_eq!(network::cost(&a.links()[0], &a, &b), 50);
+assert_eq!(network::cost(&a.links()[1], &a, &b), 7);
+# }
+```
+ */
+pub fn cost(link: &HL, from: &Node, to: &Node) -> u32 {
+    link.weight
+        .unwrap_or_else(|| coordinate::distance(from.position(), to.position()))
+}
+
+/**
+Finds the shortest path between two nodes using Dijkstra's algorithm.
+
+The cost of each leg is given by cost, so HL weights are respected.
+Each Node is only expanded once, so cyclic networks terminate.
+
+
+## Examples
+
+```
+# use pathtracer::{map::network, *};
+let nodes = Node::linked_list(Node::from_list(&[(0, 0), (10, 10), (20, 20)]));
+let network = Network::new(nodes);
+let path = network::path(&network, "C", "A", &network::path_dijkstra).unwrap();
+assert_eq!(path.len(), 3);
+```
+
+
+## Errors
+
+Error::NoPath if the path could not be found.
+ */
+pub fn path_dijkstra(network: &Network<Node>, start: Node, goal: Node) -> Result<Vec<Node>, Error> {
+    path_cost(network, start, goal, &cost)
+}
+
+/**
+Finds the shortest path between two nodes using Dijkstra's algorithm with a custom cost function.
+
+The function receives the HL being traversed, and the Nodes it connects.
+It can be passed to path by wrapping it in a closure.
+
+
+## Examples
+
+Ignores distances and finds the path with the fewest legs.
+
+```
+# use pathtracer::{map::network, *};
+let nodes = Node::linked_list(Node::from_list(&[(0, 0), (10, 10), (20, 20)]));
+let network = Network::new(nodes);
+let fewest_legs = |n: &Network<Node>, a, b| network::path_cost(n, a, b, &|_, _, _| 1);
+let path = network::path(&network, "C", "A", &fewest_legs).unwrap();
+assert_eq!(path.len(), 3);
+```
+
+
+## Errors
+
+Error::NoPath if the path could not be found.
+ */
+pub fn path_cost(
+    network: &Network<Node>,
+    start: Node,
+    goal: Node,
+    cost: &dyn Fn(&HL, &Node, &Node) -> u32,
+) -> Result<Vec<Node>, Error> {
+    search(network, &start, &goal, cost, &|_| 0)
+}
+
+/**
+Finds the shortest path between two nodes using A*.
+
+The euclidean distance to the goal is used as the heuristic, which makes it
+expand fewer Nodes than path_dijkstra on networks spread out in space.
+
+HL weights are respected. When links cost less than the exact distance between
+the Nodes they connect, by being weighted lower or by the distance being rounded down,
+the distance is scaled down by the lowest cost per unit of distance, so the path found
+costs the same as the one from path_dijkstra.
+
+
+## Errors
+
+Error::NoPath if the path could not be found.
+ */
+pub fn path_astar(network: &Network<Node>, start: Node, goal: Node) -> Result<Vec<Node>, Error> {
+    search(network, &start, &goal, &cost, &heuristic(network, &goal))
+}
+
+/**
+Returns the A* heuristic towards the goal, which never exceeds the cost of reaching it.
+ */
+fn heuristic<'a>(network: &Network<Node>, goal: &'a Node) -> impl Fn(&Node) -> u32 + 'a {
+    let scale = cost_per_distance(network);
+    move |node: &Node| (length(node.position(), goal.position()) * scale) as u32
+}
+
+/**
+Returns the lowest cost per unit of distance of any link in the network, at most 1.
+
+Scaling the distance left by it never exceeds the cost of the links which have to be followed to cover it.
+ */
+fn cost_per_distance(network: &Network<Node>) -> f64 {
+    network
+        .hash_map
+        .values()
+        .flat_map(|from| {
+            from.links()
+                .iter()
+                .filter(|link| link.is_connected())
+                .filter_map(move |link| {
+                    let to = network.lookup(link.t)?;
+                    let distance = length(from.position(), to.position());
+                    (distance > 0.0).then(|| f64::from(cost(link, from, to)) / distance)
+                })
+        })
+        .fold(1.0, f64::min)
+}
+
+/**
+Returns the exact distance between the Coordinates.
+ */
+fn length(a: Coordinate, b: Coordinate) -> f64 {
+    (f64::from(b.x) - f64::from(a.x)).hypot(f64::from(b.y) - f64::from(a.y))
+}
+
+/**
+Best-first search shared by path_dijkstra and path_astar.
+
+The returned path starts with the goal and ends with the start, matching path_shortest_leg.
+ */
+fn search(
+    network: &Network<Node>,
+    start: &Node,
+    goal: &Node,
+    cost: &dyn Fn(&HL, &Node, &Node) -> u32,
+    heuristic: &dyn Fn(&Node) -> u32,
+) -> Result<Vec<Node>, Error> {
+    let mut queue = BinaryHeap::new();
+    let mut costs: HashMap<u64, u32> = HashMap::new();
+    let mut previous: HashMap<u64, u64> = HashMap::new();
+    let mut visited: HashSet<u64> = HashSet::new();
+
+    costs.insert(start.hash, 0);
+    queue.push(Reverse((heuristic(start), 0u32, start.hash)));
+
+    while let Some(Reverse((_, acc, hash))) = queue.pop() {
+        if !visited.insert(hash) {
+            continue;
+        }
+
+        // Only the start can be missing from the network, as nothing else is queued unless found.
+        let current = network.lookup(hash).unwrap_or(start);
+        if hash == goal.hash {
+            return Ok(trace(network, start, &previous, hash));
+        }
+
+        for link in current.links().iter().filter(|x| x.is_connected()) {
+            let next = match network.lookup(link.t) {
+                Some(next) if !visited.contains(&next.hash) => next,
+                _ => continue,
+            };
+            let next_cost = acc.saturating_add(cost(link, current, next));
+            if costs.get(&next.hash).is_some_and(|&c| c <= next_cost) {
+                continue;
+            }
+            costs.insert(next.hash, next_cost);
+            previous.insert(next.hash, hash);
+            queue.push(Reverse((
+                next_cost.saturating_add(heuristic(next)),
+                next_cost,
+                next.hash,
+            )));
         }
     }
 
-    None
+    Err(Error::NoPath)
+}
+
+/**
+Walks back from the goal to the start through the recorded steps.
+ */
+fn trace(
+    network: &Network<Node>,
+    start: &Node,
+    previous: &HashMap<u64, u64>,
+    goal: u64,
+) -> Vec<Node> {
+    let mut path = Vec::new();
+    let mut hash = goal;
+    loop {
+        path.push(network.lookup(hash).unwrap_or(start).clone());
+        match previous.get(&hash) {
+            Some(&prev) => hash = prev,
+            None => return path,
+        }
+    }
 }
 
 /**
@@ -61,23 +270,25 @@ Creates a path using the 'shortest leg' in the journey at each stop.
 
 The shorest leg means that for every occurence of a path, the alternatives are sorted and the shortest is always selected.
 
+Nodes which have already been reached are not expanded again, so cyclic networks terminate.
+
 
 ## Errors
 
-The path could not be found.
+Error::NoPath if the path could not be found.
 
  */
 pub fn path_shortest_leg(
     network: &Network<Node>,
     start: Node,
     goal: Node,
-) -> io::Result<Vec<Node>> {
-    // Create a new Branch-off path.
-    let format = |mut nodes: Vec<Node>, link: &HL, acc: u32| -> WNodes {
-        let node = network.hash_map[link.t as usize % consts::NETWORK_REM].unwrap();
-        let weight = acc + coordinate::distance(nodes.first().unwrap().geo, node.geo);
+) -> Result<Vec<Node>, Error> {
+    // Create a new Branch-off path, Links to Nodes outside the network are skipped.
+    let format = |mut nodes: Vec<Node>, link: &HL, acc: u32| -> Option<WNodes> {
+        let node = network.lookup(link.t)?.clone();
+        let weight = acc.saturating_add(cost(link, nodes.first().unwrap(), &node));
         nodes.insert(0, node);
-        WNodes { weight, nodes }
+        Some(WNodes { weight, nodes })
     };
 
     // Create the queue from connected links.
@@ -85,8 +296,9 @@ pub fn path_shortest_leg(
         .links()
         .iter()
         .filter(|x| x.is_connected())
-        .map(|x| format(vec![start], x, 0))
+        .filter_map(|x| format(vec![start.clone()], x, 0))
         .collect::<Vec<_>>();
+    let mut visited: HashSet<u64> = HashSet::new();
 
     while !queue.is_empty() {
         // Sort the queue based on weight.
@@ -103,18 +315,24 @@ pub fn path_shortest_leg(
             return Ok(wnodes.nodes);
         }
 
+        // A shorter path to this Node has already been expanded.
+        if !visited.insert(current.hash) {
+            continue;
+        }
+
         // Push new paths to the queue.
         let _ = current
             .links()
             .iter()
             .filter(|x| x.is_connected())
-            .map(|x| queue.push(format(wnodes.nodes.clone(), x, wnodes.weight)))
+            .filter_map(|x| format(wnodes.nodes.clone(), x, wnodes.weight))
+            .map(|x| queue.push(x))
             .collect::<Vec<_>>();
     }
 
     // If we run out of items in the Queue, and we have not reacted
     // the goal, the path is invalid. And does not exist.
-    Err(Error::new(ErrorKind::Other, "not a valid path"))
+    Err(Error::NoPath)
 }
 
 #[cfg(test)]
@@ -133,6 +351,18 @@ mod tests {
         Network::new(nodes())
     }
 
+    // Helper, A <-> B <-> C <-> D <-> E <-> A where E is a long detour.
+    fn cyclic_network() -> Network<Node> {
+        let mut nodes = Node::from_list(&[(0, 0), (10, 0), (20, 0), (30, 0), (15, 100)]);
+        for i in 0..nodes.len() {
+            let j = (i + 1) % nodes.len();
+            let (a, b) = (nodes[i].clone(), nodes[j].clone());
+            nodes[i].link(&b);
+            nodes[j].link(&a);
+        }
+        Network::new(nodes)
+    }
+
     #[test]
     fn simple_network_shortest_leg() {
         let network = network();
@@ -165,13 +395,131 @@ mod tests {
         let path = net.path("A", "D").unwrap();
         path_sl.reverse();
 
-        let f = |&p: &Node| p.geo;
+        let f = |p: &Node| p.geo;
         let v1 = path.iter().map(f).collect::<Vec<_>>();
         let v2 = path_sl.iter().map(f).collect::<Vec<_>>();
 
         assert_eq!(v1, v2);
     }
 
+    #[test]
+    fn simple_network_dijkstra() {
+        let path = path(&network(), "D", "A", &path_dijkstra).unwrap();
+        assert_eq!(path.len(), 4);
+        assert_eq!(path[0], get(&network(), "A").unwrap());
+    }
+
+    #[test]
+    fn simple_network_astar() {
+        let path = path(&network(), "D", "A", &path_astar).unwrap();
+        assert_eq!(path.len(), 4);
+    }
+
+    #[test]
+    fn same_node_path() {
+        let path = path(&network(), "B", "B", &path_dijkstra).unwrap();
+        assert_eq!(path.len(), 1);
+    }
+
+    #[test]
+    fn cyclic_network_terminates() {
+        let mut n = cyclic_network();
+        n.insert(node!("F", 50, 50));
+        assert!(path(&n, "A", "F", &path_dijkstra).is_err());
+        assert!(path(&n, "A", "F", &path_astar).is_err());
+        assert!(path(&n, "A", "F", &path_shortest_leg).is_err());
+    }
+
+    #[test]
+    fn cyclic_network_shortest() {
+        let net = cyclic_network();
+        let f = |p: Vec<Node>| p.iter().map(|n| n.hash).collect::<Vec<_>>();
+        let expected = f(path(&net, "A", "D", &path_shortest_leg).unwrap());
+        assert_eq!(expected.len(), 4);
+        assert_eq!(f(path(&net, "A", "D", &path_dijkstra).unwrap()), expected);
+        assert_eq!(f(path(&net, "A", "D", &path_astar).unwrap()), expected);
+    }
+
+    #[test]
+    fn weighted_astar_is_shortest() {
+        // The detour through F is cheap, but F is far away from G.
+        let g = node!("G", 100, 0);
+        let mut f = node!("F", 0, 300);
+        let mut s = node!("S", 0, 0);
+        f.link_weighted(&g, 1);
+        s.link_weighted(&f, 1);
+        s.link(&g);
+        let net = Network::new(vec![s, f, g]);
+
+        let hashes = |p: Vec<Node>| p.iter().map(|n| n.hash).collect::<Vec<_>>();
+        let expected = hashes(path(&net, "S", "G", &path_dijkstra).unwrap());
+        assert_eq!(expected.len(), 3);
+        assert_eq!(hashes(path(&net, "S", "G", &path_astar).unwrap()), expected);
+    }
+
+    #[test]
+    fn truncated_distances_keep_astar_admissible() {
+        // Each diagonal leg is about 1.41 long, but costs 1 as distances are rounded down.
+        let nodes = Node::from_list(&[(5, 5), (4, 4), (3, 3), (2, 2), (1, 1), (0, 0)]);
+        let net = Network::new(Node::linked_list(nodes));
+        let (start, goal) = (get(&net, "F").unwrap(), get(&net, "A").unwrap());
+        let shortest = path_dijkstra(&net, start.clone(), goal.clone()).unwrap();
+        let total = shortest
+            .windows(2)
+            .map(|w| coordinate::distance(w[0].position(), w[1].position()))
+            .sum::<u32>();
+        assert_eq!(total, 5);
+        for node in &shortest {
+            let left = coordinate::distance(node.position(), goal.position());
+            assert!(
+                heuristic(&net, &goal)(node) <= left.min(total),
+                "{:?}",
+                node.geo
+            );
+        }
+        assert_eq!(heuristic(&net, &goal)(&start), 5);
+        assert_eq!(path_astar(&net, start, goal).unwrap().len(), 6);
+    }
+
+    #[test]
+    fn shortest_leg_saturates() {
+        let mut nodes = Node::from_list(&[(0, 0), (10, 0), (20, 0)]);
+        let (b, c) = (nodes[1].clone(), nodes[2].clone());
+        nodes[0].link_weighted(&b, u32::MAX);
+        nodes[1].link_weighted(&c, u32::MAX);
+        let net = Network::new(nodes);
+        assert_eq!(path(&net, "A", "C", &path_shortest_leg).unwrap().len(), 3);
+    }
+
+    #[test]
+    fn far_apart_nodes() {
+        let nodes = Node::from_list(&[(-2_000_000_000, 0), (0, 0), (2_000_000_000, 0)]);
+        let net = Network::new(Node::linked_list(nodes));
+        assert_eq!(path(&net, "C", "A", &path_dijkstra).unwrap().len(), 3);
+        assert_eq!(path(&net, "C", "A", &path_astar).unwrap().len(), 3);
+    }
+
+    #[test]
+    fn weighted_network() {
+        let mut nodes = Node::from_list(&[(0, 0), (10, 0), (20, 0)]);
+        let (b, c) = (nodes[1].clone(), nodes[2].clone());
+        nodes[0].link(&b);
+        nodes[1].link(&c);
+        nodes[0].link_weighted(&c, 100);
+        let net = Network::new(nodes);
+
+        assert_eq!(path(&net, "A", "C", &path_shortest_leg).unwrap().len(), 3);
+        assert_eq!(path(&net, "A", "C", &path_dijkstra).unwrap().len(), 3);
+        assert_eq!(path(&net, "A", "C", &path_astar).unwrap().len(), 3);
+
+        let ignore_weights = |n: &Network<Node>, a, b| {
+            path_cost(n, a, b, &|_, from: &Node, to: &Node| {
+                coordinate::distance(from.position(), to.position()) + 1
+            })
+        };
+        assert_eq!(path(&net, "A", "C", &ignore_weights).unwrap().len(), 2);
+    }
+
     #[test]
     fn valid_gets() {
         let network = network();
@@ -188,11 +536,45 @@ mod tests {
         }
     }
 
+    #[test]
+    fn large_network_gets() {
+        let list = (0..2000).map(|i| (i, i)).collect::<Vec<_>>();
+        let network = Network::new(Node::from_list(&list));
+        assert_eq!(network.len(), list.len());
+        for node in Node::from_list(&list) {
+            assert!(network.contains(node.hash));
+        }
+    }
+
+    #[test]
+    fn insert_and_remove() {
+        let mut network = network();
+        let e = node!("E", 40, 40);
+        let hash = e.hash;
+        assert!(network.insert(e).is_none());
+        assert!(get(&network, "E").is_some());
+        assert!(network.remove(hash).is_some());
+        assert!(get(&network, "E").is_none());
+        assert_eq!(network.len(), 4);
+    }
+
     #[test]
     fn invalid_network_1() {
         assert!(network().path("B", "E").is_err());
     }
 
+    #[test]
+    fn invalid_network_errors() {
+        match network().path("B", "E") {
+            Err(Error::NodeNotFound(name)) => assert_eq!(name, "E"),
+            e => panic!("expected NodeNotFound, got {:?}", e),
+        }
+        match network().path("D", "A") {
+            Err(Error::NoPath) => (),
+            e => panic!("expected NoPath, got {:?}", e),
+        }
+    }
+
     #[test]
     fn invalid_network_2() {
         assert!(network().path("Testing", "One, two, Three.").is_err());
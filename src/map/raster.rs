@@ -0,0 +1,628 @@
+/*!
+Anti-aliased rasterization.
+
+Lines a pixel wide are drawn using Xiaolin Wu's algorithm, thicker lines and circles by how much
+of each pixel they cover. Pixels along the edges are blended with what is already drawn,
+instead of being either fully set or left untouched.
+ */
+
+use super::{super::*, theme::Paint};
+use image::Rgba;
+use std::{collections::HashMap, ops::RangeInclusive};
+
+/**
+How shapes and lines are turned in to pixels.
+
+
+## Aliased
+
+Every pixel is either fully drawn or not at all, using the plotting functions in tools.
+
+
+## AntiAliased
+
+Edges are blended with the background, and lines are drawn with their real thickness.
+ */
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Rasterizer {
+    #[default]
+    Aliased,
+    AntiAliased,
+}
+
+/**
+How much of each pixel is covered, between 0 and 1.
+
+Overlapping parts keep the highest coverage, so joints between segments are not drawn twice.
+Pixels outside of the image are left out.
+ */
+struct Coverage {
+    pixels: HashMap<(i32, i32), f64>,
+    width: i32,
+    height: i32,
+}
+
+impl Coverage {
+    fn new(image: &IW) -> Self {
+        let size = image.dimensions();
+        Coverage {
+            pixels: HashMap::new(),
+            width: size.x,
+            height: size.y,
+        }
+    }
+
+    /**
+    Returns the columns and rows of the pixels between min and max which are inside of the image.
+     */
+    fn clip(&self, min: Point, max: Point) -> (RangeInclusive<i32>, RangeInclusive<i32>) {
+        let range = |from: f64, to: f64, size: i32| {
+            let from = from.floor().max(0.0);
+            let to = to.ceil().min(f64::from(size - 1));
+            // The range is empty when the pixels are all outside.
+            from as i32..=to as i32
+        };
+        (
+            range(min.x, max.x, self.width),
+            range(min.y, max.y, self.height),
+        )
+    }
+
+    fn add(&mut self, x: i32, y: i32, coverage: f64) {
+        if coverage <= 0.0 || x < 0 || y < 0 || x >= self.width || y >= self.height {
+            return;
+        }
+        let c = self.pixels.entry((x, y)).or_insert(0.0);
+        *c = c.max(coverage.min(1.0));
+    }
+
+    fn blend(self, image: &mut IW, color: &dyn Fn(Point) -> Rgba<u8>) {
+        for ((x, y), coverage) in self.pixels {
+            let pixel = coordinate!(x, y);
+            image.blend(&pixel, color(Point::from(pixel)), coverage);
+        }
+    }
+
+    /**
+    Adds a line a pixel wide.
+
+    Derived from: https://en.wikipedia.org/wiki/Xiaolin_Wu%27s_line_algorithm
+     */
+    fn wu(&mut self, mut a: Point, mut b: Point) {
+        let steep = (b.y - a.y).abs() > (b.x - a.x).abs();
+        if steep {
+            a = Point::new(a.y, a.x);
+            b = Point::new(b.y, b.x);
+        }
+        if a.x > b.x {
+            std::mem::swap(&mut a, &mut b);
+        }
+        // Only the columns along the line which are inside of the image are walked.
+        let columns = if steep { self.height } else { self.width };
+        let mut plot = |x: i32, y: i32, c: f64| {
+            if steep {
+                self.add(y, x, c);
+            } else {
+                self.add(x, y, c);
+            }
+        };
+
+        let dx = b.x - a.x;
+        let gradient = if dx == 0.0 { 1.0 } else { (b.y - a.y) / dx };
+
+        // The end points cover only part of their pixels.
+        let x_start = a.x.round();
+        let y_start = a.y + gradient * (x_start - a.x);
+        let gap = 1.0 - (a.x + 0.5).fract();
+        plot(
+            x_start as i32,
+            y_start.floor() as i32,
+            (1.0 - y_start.fract()) * gap,
+        );
+        plot(
+            x_start as i32,
+            y_start.floor() as i32 + 1,
+            y_start.fract() * gap,
+        );
+
+        let x_end = b.x.round();
+        let y_end = b.y + gradient * (x_end - b.x);
+        let gap = (b.x + 0.5).fract();
+        plot(
+            x_end as i32,
+            y_end.floor() as i32,
+            (1.0 - y_end.fract()) * gap,
+        );
+        plot(x_end as i32, y_end.floor() as i32 + 1, y_end.fract() * gap);
+
+        let from = (x_start as i32).saturating_add(1).max(0);
+        for x in from..(x_end as i32).min(columns) {
+            let y = y_start + gradient * (f64::from(x) - x_start);
+            plot(x, y.floor() as i32, 1.0 - y.fract());
+            plot(x, y.floor() as i32 + 1, y.fract());
+        }
+    }
+
+    /**
+    Adds a line with round ends, by the distance from each pixel center to the line.
+     */
+    fn segment(&mut self, a: Point, b: Point, width: f64) {
+        let r = width / 2.0;
+        // Pixels further away than this from the segment are not covered.
+        let reach = r + 1.0;
+        let min = Point::new(a.x.min(b.x) - reach, a.y.min(b.y) - reach);
+        let max = Point::new(a.x.max(b.x) + reach, a.y.max(b.y) + reach);
+        let (_, rows) = self.clip(min, max);
+        for y in rows {
+            // The pixels within reach of the segment on the row are a single run, as the area is convex.
+            let Some((from, to)) = row_span(a, b, reach, f64::from(y)) else {
+                continue;
+            };
+            let from = Point::new(from.max(min.x), 0.0);
+            let to = Point::new(to.min(max.x), 0.0);
+            let (columns, _) = self.clip(from, to);
+            for x in columns {
+                let d = distance(Point::new(f64::from(x), f64::from(y)), a, b);
+                self.add(x, y, r + 0.5 - d);
+            }
+        }
+    }
+}
+
+/**
+Returns the leftmost and rightmost x on the row y which are within reach of the segment between a and b.
+
+The middle of the segment is widened to the whole band along the line, which the callers limit to the segment's bounds.
+ */
+fn row_span(a: Point, b: Point, reach: f64, y: f64) -> Option<(f64, f64)> {
+    let mut span: Option<(f64, f64)> = None;
+    let mut include = |from: f64, to: f64| {
+        span = Some(span.map_or((from, to), |(f, t)| (f.min(from), t.max(to))));
+    };
+    for end in [a, b] {
+        let dy = y - end.y;
+        if dy.abs() <= reach {
+            let dx = (reach * reach - dy * dy).sqrt();
+            include(end.x - dx, end.x + dx);
+        }
+    }
+    let ab = b - a;
+    if ab.y != 0.0 {
+        // Where the line crosses the row, and how far along the row the band reaches on either side of it.
+        let cross = a.x + (y - a.y) * ab.x / ab.y;
+        let half = reach * ab.x.hypot(ab.y) / ab.y.abs();
+        include(cross - half, cross + half);
+    } else if (y - a.y).abs() <= reach {
+        include(a.x.min(b.x), a.x.max(b.x));
+    }
+    span
+}
+
+/**
+Returns the distance from p to the closest point on the segment between a and b.
+ */
+fn distance(p: Point, a: Point, b: Point) -> f64 {
+    let ab = b - a;
+    let length = ab.x * ab.x + ab.y * ab.y;
+    let t = if length == 0.0 {
+        0.0
+    } else {
+        (((p.x - a.x) * ab.x + (p.y - a.y) * ab.y) / length).clamp(0.0, 1.0)
+    };
+    let closest = a + Point::new(ab.x * t, ab.y * t);
+    (p.x - closest.x).hypot(p.y - closest.y)
+}
+
+/**
+Draws a line through all the points, with the given width in pixels.
+
+Lines a pixel wide or thinner use Wu's algorithm. A gradient goes from the first to the last point.
+
+
+## Examples
+
+```
+# use pathtracer::{map::raster, *};
+let mut image = Map::new().size(20, 20).map::<Node>(&[]).consume();
+let line = [Point::new(2.0, 2.0), Point::new(17.0, 9.0)];
+raster::polyline(&mut image, &line, 3.0, image::Rgba([255, 255, 255, 255]).into());
+assert_eq!(image.image().get_pixel(2, 2).0, [255, 255, 255, 255]);
+```
+ */
+pub fn polyline(image: &mut IW, points: &[Point], width: f64, paint: Paint) {
+    let mut coverage = Coverage::new(image);
+    for w in points.windows(2) {
+        if width <= 1.0 {
+            coverage.wu(w[0], w[1]);
+        } else {
+            coverage.segment(w[0], w[1], width);
+        }
+    }
+    if let (Some(&first), Some(&last)) = (points.first(), points.last()) {
+        coverage.blend(image, &|p| paint.along(first, last, p));
+    }
+}
+
+/**
+Draws a line through all the points like polyline, but only the parts of the pattern which are on.
+
+The pattern is the length of each dash followed by the gap after it, in pixels along the line.
+Round ends are kept within the dashes, so the gaps stay visible on thick lines.
+
+
+## Examples
+
+```
+# use pathtracer::{map::raster, *};
+let mut image = Map::new().size(20, 5).map::<Node>(&[]).consume();
+let line = [Point::new(0.0, 2.0), Point::new(19.0, 2.0)];
+raster::dashed(&mut image, &line, 1.0, image::Rgba([255, 255, 255, 255]).into(), (4, 4));
+assert_eq!(image.image().get_pixel(2, 2).0, [255, 255, 255, 255]);
+assert_eq!(image.image().get_pixel(6, 2).0, [0, 0, 0, 255]);
+```
+ */
+pub fn dashed(image: &mut IW, points: &[Point], width: f64, paint: Paint, pattern: (u32, u32)) {
+    let (on, off) = (f64::from(pattern.0), f64::from(pattern.1));
+    let length = |a: Point, b: Point| (b.x - a.x).hypot(b.y - a.y);
+    let total = points.windows(2).map(|w| length(w[0], w[1])).sum::<f64>();
+    if on + off <= 0.0 {
+        return;
+    }
+    let cap = if width <= 1.0 { 0.0 } else { width / 2.0 };
+
+    // Dashes are only generated along the parts of the line near the image.
+    let size = image.dimensions();
+    let reach = width / 2.0 + 1.0;
+    let min = Point::new(-reach, -reach);
+    let max = Point::new(f64::from(size.x) + reach, f64::from(size.y) + reach);
+
+    let mut coverage = Coverage::new(image);
+    // Dashes crossing between visible parts are not drawn again.
+    let mut next = 0.0_f64;
+    for (from, to) in visible(points, min, max) {
+        let mut start = next.max((from / (on + off)).floor() * (on + off));
+        while start < to.min(total) {
+            let (mut a, mut b) = (start + cap, (start + on).min(total) - cap);
+            if a > b {
+                a = (a + b) / 2.0;
+                b = a;
+            }
+            let dash = slice(points, a, b);
+            for w in dash.windows(2) {
+                if width <= 1.0 {
+                    coverage.wu(w[0], w[1]);
+                } else {
+                    coverage.segment(w[0], w[1], width);
+                }
+            }
+            start += on + off;
+        }
+        next = start;
+    }
+    if let (Some(&first), Some(&last)) = (points.first(), points.last()) {
+        coverage.blend(image, &|p| paint.along(first, last, p));
+    }
+}
+
+/**
+Returns the ranges of distances along the line where it is within the rectangle between min and max.
+
+Derived from: https://en.wikipedia.org/wiki/Liang%E2%80%93Barsky_algorithm
+ */
+fn visible(points: &[Point], min: Point, max: Point) -> Vec<(f64, f64)> {
+    let mut ranges: Vec<(f64, f64)> = Vec::new();
+    let mut travelled = 0.0;
+    for w in points.windows(2) {
+        let d = w[1] - w[0];
+        let length = d.x.hypot(d.y);
+        let (mut t0, mut t1) = (0.0_f64, 1.0_f64);
+        for (p, q) in [
+            (-d.x, w[0].x - min.x),
+            (d.x, max.x - w[0].x),
+            (-d.y, w[0].y - min.y),
+            (d.y, max.y - w[0].y),
+        ] {
+            if p == 0.0 {
+                if q < 0.0 {
+                    t1 = -1.0;
+                }
+            } else if p < 0.0 {
+                t0 = t0.max(q / p);
+            } else {
+                t1 = t1.min(q / p);
+            }
+        }
+        if t0 <= t1 {
+            let range = (travelled + t0 * length, travelled + t1 * length);
+            match ranges.last_mut() {
+                Some(last) if last.1 >= range.0 => last.1 = last.1.max(range.1),
+                _ => ranges.push(range),
+            }
+        }
+        travelled += length;
+    }
+    ranges
+}
+
+/**
+Returns the part of the line between the distances a and b along it.
+ */
+pub(crate) fn slice(points: &[Point], a: f64, b: f64) -> Vec<Point> {
+    let mut result = Vec::new();
+    let mut travelled = 0.0;
+    for w in points.windows(2) {
+        let length = (w[1].x - w[0].x).hypot(w[1].y - w[0].y);
+        let at = |d: f64| {
+            let t = if length == 0.0 {
+                0.0
+            } else {
+                (d - travelled) / length
+            };
+            w[0] + Point::new((w[1].x - w[0].x) * t, (w[1].y - w[0].y) * t)
+        };
+        let end = travelled + length;
+        if a <= end && b >= travelled {
+            if result.is_empty() {
+                result.push(at(a.max(travelled)));
+            }
+            result.push(at(b.min(end)));
+        }
+        travelled = end;
+    }
+    if result.len() == 1 {
+        result.push(result[0]);
+    }
+    result
+}
+
+/**
+Draws a filled circle.
+ */
+pub fn circle(image: &mut IW, center: Point, radius: f64, color: Rgba<u8>) {
+    let mut coverage = Coverage::new(image);
+    coverage.segment(center, center, radius * 2.0);
+    coverage.blend(image, &|_| color);
+}
+
+/**
+Draws a filled triangle, with the edges sampled 4 times in each direction per pixel.
+ */
+pub fn triangle(image: &mut IW, corners: [Point; 3], color: Rgba<u8>) {
+    let [a, b, c] = corners;
+    let edge = |p: Point, q: Point, r: Point| (q.x - p.x) * (r.y - p.y) - (q.y - p.y) * (r.x - p.x);
+    let inside = |p: Point| {
+        let (d1, d2, d3) = (edge(a, b, p), edge(b, c, p), edge(c, a, p));
+        !((d1 < 0.0 || d2 < 0.0 || d3 < 0.0) && (d1 > 0.0 || d2 > 0.0 || d3 > 0.0))
+    };
+
+    let mut coverage = Coverage::new(image);
+    let min = Point::new(a.x.min(b.x).min(c.x), a.y.min(b.y).min(c.y));
+    let max = Point::new(a.x.max(b.x).max(c.x), a.y.max(b.y).max(c.y));
+    let (columns, rows) = coverage.clip(min, max);
+    for x in columns {
+        for y in rows.clone() {
+            let mut hits = 0;
+            for i in 0..4 {
+                for j in 0..4 {
+                    let sample = Point::new(
+                        f64::from(x) - 0.375 + f64::from(i) * 0.25,
+                        f64::from(y) - 0.375 + f64::from(j) * 0.25,
+                    );
+                    if inside(sample) {
+                        hits += 1;
+                    }
+                }
+            }
+            coverage.add(x, y, f64::from(hits) / 16.0);
+        }
+    }
+    coverage.blend(image, &|_| color);
+}
+
+/**
+Draws a filled polygon with the corners, sampled 4 times in each direction per pixel.
+ */
+pub fn polygon(image: &mut IW, corners: &[Point], color: Rgba<u8>) {
+    let (mut min, mut max) = match corners.first() {
+        Some(&first) => (first, first),
+        None => return,
+    };
+    for c in corners {
+        min = Point::new(min.x.min(c.x), min.y.min(c.y));
+        max = Point::new(max.x.max(c.x), max.y.max(c.y));
+    }
+
+    let mut coverage = Coverage::new(image);
+    let (columns, rows) = coverage.clip(min, max);
+    for x in columns {
+        for y in rows.clone() {
+            let mut hits = 0;
+            for i in 0..4 {
+                for j in 0..4 {
+                    let sample = Point::new(
+                        f64::from(x) - 0.375 + f64::from(i) * 0.25,
+                        f64::from(y) - 0.375 + f64::from(j) * 0.25,
+                    );
+                    if tools::inside(corners, sample) {
+                        hits += 1;
+                    }
+                }
+            }
+            coverage.add(x, y, f64::from(hits) / 16.0);
+        }
+    }
+    coverage.blend(image, &|_| color);
+}
+
+/**
+Draws a ring between the outer and inner radius.
+ */
+pub fn ring(image: &mut IW, center: Point, outer: f64, inner: f64, color: Rgba<u8>) {
+    let mut coverage = Coverage::new(image);
+    let r = outer.ceil() as i32 + 1;
+    for x in -r..=r {
+        for y in -r..=r {
+            let d = f64::from(x).hypot(f64::from(y));
+            let c = (outer + 0.5 - d).min(d - inner + 0.5);
+            let p = center + Point::new(f64::from(x), f64::from(y));
+            coverage.add(p.x.round() as i32, p.y.round() as i32, c);
+        }
+    }
+    coverage.blend(image, &|_| color);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WHITE: Rgba<u8> = Rgba([255, 255, 255, 255]);
+
+    fn canvas() -> IW {
+        Map::new().size(40, 40).map::<Node>(&[]).consume()
+    }
+
+    fn shade(image: &IW, x: u32, y: u32) -> u8 {
+        image.image().get_pixel(x, y).0[0]
+    }
+
+    #[test]
+    fn wu_blends_edges() {
+        let mut image = canvas();
+        polyline(
+            &mut image,
+            &[Point::new(0.0, 0.0), Point::new(30.0, 10.0)],
+            1.0,
+            WHITE.into(),
+        );
+        assert_eq!(shade(&image, 3, 1), 255);
+        // Between two rows, both pixels are partly covered.
+        assert_eq!(shade(&image, 16, 5), 170);
+        assert_eq!(shade(&image, 16, 6), 85);
+        assert_eq!(shade(&image, 15, 20), 0);
+    }
+
+    #[test]
+    fn clipped_to_the_image() {
+        let image = canvas();
+        let mut coverage = Coverage::new(&image);
+        coverage.segment(Point::new(0.0, 0.0), Point::new(20000.0, 20000.0), 3.0);
+        assert!(coverage.pixels.len() < 40 * 6);
+        assert_eq!(coverage.pixels.get(&(20, 20)), Some(&1.0));
+        assert!(coverage
+            .pixels
+            .keys()
+            .all(|&(x, y)| (0..40).contains(&x) && (0..40).contains(&y)));
+
+        let mut coverage = Coverage::new(&image);
+        coverage.segment(Point::new(-50.0, -50.0), Point::new(-10.0, 90.0), 5.0);
+        assert!(coverage.pixels.is_empty());
+    }
+
+    #[test]
+    fn wu_clipped_to_the_image() {
+        let image = canvas();
+        let mut coverage = Coverage::new(&image);
+        coverage.wu(Point::new(0.0, 0.0), Point::new(2_000_000_000.0, 1.0));
+        assert!(coverage.pixels[&(20, 0)] > 0.99);
+        assert!(coverage.pixels.len() <= 40 * 2);
+
+        let mut coverage = Coverage::new(&image);
+        coverage.wu(Point::new(-2_000_000_000.0, 20.0), Point::new(-10.0, 20.0));
+        assert!(coverage.pixels.is_empty());
+    }
+
+    #[test]
+    fn dashes_clipped_to_the_image() {
+        let mut image = canvas();
+        let line = [Point::new(0.0, 20.0), Point::new(2_000_000_000.0, 21.0)];
+        dashed(&mut image, &line, 1.0, WHITE.into(), (4, 4));
+        assert!(shade(&image, 2, 20) > 200);
+        assert_eq!(shade(&image, 6, 20), 0);
+
+        // The pattern continues from where the line enters the image.
+        let mut image = canvas();
+        let line = [
+            Point::new(-1_000_000_003.0, 20.0),
+            Point::new(1_000_000_000.0, 20.0),
+        ];
+        dashed(&mut image, &line, 3.0, WHITE.into(), (4, 4));
+        let mut plain = canvas();
+        let line = [Point::new(-3.0, 20.0), Point::new(40.0, 20.0)];
+        dashed(&mut plain, &line, 3.0, WHITE.into(), (4, 4));
+        for x in 0..40 {
+            let (a, b) = (shade(&image, x, 20), shade(&plain, x, 20));
+            assert!(
+                (i32::from(a) - i32::from(b)).abs() <= 2,
+                "{}: {} {}",
+                x,
+                a,
+                b
+            );
+        }
+    }
+
+    #[test]
+    fn thick_lines() {
+        let mut image = canvas();
+        polyline(
+            &mut image,
+            &[Point::new(5.0, 20.0), Point::new(35.0, 20.0)],
+            6.0,
+            WHITE.into(),
+        );
+        for y in 18..=22 {
+            assert_eq!(shade(&image, 20, y), 255, "{}", y);
+        }
+        assert_eq!(shade(&image, 20, 25), 0);
+        assert_eq!(shade(&image, 20, 14), 0);
+    }
+
+    #[test]
+    fn joints_are_not_drawn_twice() {
+        let mut image = canvas();
+        let half = Rgba([128, 128, 128, 255]);
+        let line = [
+            Point::new(5.0, 5.0),
+            Point::new(20.0, 5.5),
+            Point::new(35.0, 5.0),
+        ];
+        polyline(&mut image, &line, 3.0, half.into());
+        assert_eq!(shade(&image, 20, 5), 128);
+    }
+
+    #[test]
+    fn circles() {
+        let mut image = canvas();
+        circle(&mut image, Point::new(20.0, 20.0), 10.0, WHITE);
+        assert_eq!(shade(&image, 20, 20), 255);
+        assert_eq!(shade(&image, 20, 31), 0);
+        let edge = shade(&image, 27, 27);
+        assert!(edge > 0 && edge < 255, "{}", edge);
+    }
+
+    #[test]
+    fn triangles() {
+        let mut image = canvas();
+        let corners = [
+            Point::new(20.0, 0.0),
+            Point::new(0.0, 39.0),
+            Point::new(39.0, 39.0),
+        ];
+        triangle(&mut image, corners, WHITE);
+        assert_eq!(shade(&image, 20, 30), 255);
+        assert_eq!(shade(&image, 2, 2), 0);
+        let edge = shade(&image, 10, 20);
+        assert!(edge > 0 && edge < 255, "{}", edge);
+    }
+
+    #[test]
+    fn gradients() {
+        let mut image = canvas();
+        let paint = Paint::Gradient(WHITE, Rgba([0, 0, 255, 255]));
+        let line = [Point::new(0.0, 20.0), Point::new(39.0, 20.0)];
+        polyline(&mut image, &line, 3.0, paint);
+        assert_eq!(image.image().get_pixel(0, 20).0, [255, 255, 255, 255]);
+        assert_eq!(image.image().get_pixel(39, 20).0, [0, 0, 255, 255]);
+        assert_eq!(image.image().get_pixel(20, 20).0[0], 124);
+    }
+}
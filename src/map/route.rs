@@ -0,0 +1,226 @@
+/*!
+Orthogonal edge routing.
+
+Edges are routed along horizontal and vertical lines which pass beside the obstacles, preferring
+routes which are short and bend few times. Obstacles are areas of the canvas given by their top left and
+bottom right corner, usually the Nodes drawn on it.
+ */
+
+use super::super::*;
+use std::{
+    cmp::{self, Reverse},
+    collections::{BinaryHeap, HashMap},
+};
+
+/**
+Space kept between a route and the obstacles it passes.
+ */
+const MARGIN: i32 = 3;
+
+/**
+The cost of a bend, in pixels of length.
+ */
+const BEND: i32 = 20;
+
+/**
+Returns the corners of a route from from to to, made of horizontal and vertical lines which do not pass through the obstacles.
+
+Obstacles which contain either end are ignored, as the route has to enter them.
+If there is no such route, the route bends once, going horizontally first.
+
+
+## Examples
+
+```
+# use pathtracer::{map::route, *};
+let from = Coordinate::new(0, 0);
+let to = Coordinate::new(40, 0);
+let node = (Coordinate::new(15, -5), Coordinate::new(25, 5));
+let route = route::orthogonal(from, to, &[node]);
+assert_eq!(route.first(), Some(&from));
+assert_eq!(route.last(), Some(&to));
+// The route goes around the Node in the way.
+assert!(route.iter().any(|c| c.y != 0));
+```
+ */
+pub fn orthogonal(
+    from: Coordinate,
+    to: Coordinate,
+    obstacles: &[(Coordinate, Coordinate)],
+) -> Vec<Coordinate> {
+    let fallback = || {
+        if from.x == to.x || from.y == to.y {
+            vec![from, to]
+        } else {
+            vec![from, coordinate!(to.x, from.y), to]
+        }
+    };
+
+    // Only obstacles near the ends can be in the way of a reasonable route.
+    let reach = cmp::max((to.x - from.x).abs(), (to.y - from.y).abs()) / 2 + 20;
+    let area = (
+        coordinate!(
+            cmp::min(from.x, to.x) - reach,
+            cmp::min(from.y, to.y) - reach
+        ),
+        coordinate!(
+            cmp::max(from.x, to.x) + reach,
+            cmp::max(from.y, to.y) + reach
+        ),
+    );
+    let boxes = obstacles
+        .iter()
+        .map(|&(min, max)| (min - coordinate!(MARGIN), max + coordinate!(MARGIN)))
+        .filter(|&b| touches(b, area) && !contains(b, from) && !contains(b, to))
+        .collect::<Vec<_>>();
+
+    let mut xs = vec![from.x, to.x];
+    let mut ys = vec![from.y, to.y];
+    for (min, max) in &boxes {
+        xs.extend([min.x - 1, max.x + 1]);
+        ys.extend([min.y - 1, max.y + 1]);
+    }
+    for list in [&mut xs, &mut ys] {
+        list.sort_unstable();
+        list.dedup();
+    }
+
+    let index = |list: &[i32], v: i32| list.binary_search(&v).unwrap();
+    let start = (index(&xs, from.x), index(&ys, from.y));
+    let goal = (index(&xs, to.x), index(&ys, to.y));
+    let point = |(x, y): (usize, usize)| coordinate!(xs[x], ys[y]);
+    let blocked = |a: Coordinate, b: Coordinate| {
+        let segment = (
+            coordinate!(cmp::min(a.x, b.x), cmp::min(a.y, b.y)),
+            coordinate!(cmp::max(a.x, b.x), cmp::max(a.y, b.y)),
+        );
+        boxes.iter().any(|&b| touches(b, segment))
+    };
+
+    // Dijkstra over the grid of lines, where the direction arrived from is part of the state to count bends.
+    const DIRECTIONS: [(isize, isize); 4] = [(1, 0), (-1, 0), (0, 1), (0, -1)];
+    type State = ((usize, usize), usize);
+    let mut cost: HashMap<State, i32> = HashMap::new();
+    let mut previous: HashMap<State, State> = HashMap::new();
+    let mut queue = BinaryHeap::new();
+    for d in 0..DIRECTIONS.len() {
+        cost.insert((start, d), 0);
+        queue.push(Reverse((0, start, d)));
+    }
+
+    let mut end = None;
+    while let Some(Reverse((c, at, d))) = queue.pop() {
+        if cost.get(&(at, d)).is_some_and(|&best| best < c) {
+            continue;
+        }
+        if at == goal {
+            end = Some((at, d));
+            break;
+        }
+        for (nd, &(dx, dy)) in DIRECTIONS.iter().enumerate() {
+            let next = match (at.0.checked_add_signed(dx), at.1.checked_add_signed(dy)) {
+                (Some(x), Some(y)) if x < xs.len() && y < ys.len() => (x, y),
+                _ => continue,
+            };
+            let (a, b) = (point(at), point(next));
+            if blocked(a, b) {
+                continue;
+            }
+            let bend = if nd == d || at == start { 0 } else { BEND };
+            let next_cost = c + (b.x - a.x).abs() + (b.y - a.y).abs() + bend;
+            if cost.get(&(next, nd)).is_none_or(|&best| next_cost < best) {
+                cost.insert((next, nd), next_cost);
+                previous.insert((next, nd), (at, d));
+                queue.push(Reverse((next_cost, next, nd)));
+            }
+        }
+    }
+
+    let mut state = match end {
+        Some(state) => state,
+        None => return fallback(),
+    };
+    let mut route = vec![point(state.0)];
+    while let Some(&prev) = previous.get(&state) {
+        route.push(point(prev.0));
+        state = prev;
+    }
+    route.reverse();
+    simplify(route)
+}
+
+/**
+Removes the corners which lie on a straight line between their neighbours.
+ */
+fn simplify(route: Vec<Coordinate>) -> Vec<Coordinate> {
+    let mut result: Vec<Coordinate> = Vec::with_capacity(route.len());
+    for c in route {
+        if let [.., a, b] = result[..] {
+            if (a.x == b.x && b.x == c.x) || (a.y == b.y && b.y == c.y) {
+                result.pop();
+            }
+        }
+        result.push(c);
+    }
+    result
+}
+
+fn contains((min, max): (Coordinate, Coordinate), c: Coordinate) -> bool {
+    min.x <= c.x && c.x <= max.x && min.y <= c.y && c.y <= max.y
+}
+
+/**
+Returns true if the areas share any pixels, including their borders.
+ */
+fn touches(a: (Coordinate, Coordinate), b: (Coordinate, Coordinate)) -> bool {
+    a.0.x <= b.1.x && b.0.x <= a.1.x && a.0.y <= b.1.y && b.0.y <= a.1.y
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn clear(route: &[Coordinate], obstacles: &[(Coordinate, Coordinate)]) -> bool {
+        route.windows(2).all(|w| {
+            let segment = (
+                coordinate!(cmp::min(w[0].x, w[1].x), cmp::min(w[0].y, w[1].y)),
+                coordinate!(cmp::max(w[0].x, w[1].x), cmp::max(w[0].y, w[1].y)),
+            );
+            obstacles.iter().all(|&o| !touches(o, segment))
+        })
+    }
+
+    #[test]
+    fn straight_when_clear() {
+        let route = orthogonal(coordinate!(0, 0), coordinate!(50, 0), &[]);
+        assert_eq!(route, vec![coordinate!(0, 0), coordinate!(50, 0)]);
+        let route = orthogonal(coordinate!(0, 0), coordinate!(50, 30), &[]);
+        assert_eq!(route.len(), 3);
+        assert!(route
+            .windows(2)
+            .all(|w| w[0].x == w[1].x || w[0].y == w[1].y));
+    }
+
+    #[test]
+    fn avoids_obstacles() {
+        let obstacles = [
+            (coordinate!(20, -10), coordinate!(30, 10)),
+            (coordinate!(60, 20), coordinate!(70, 40)),
+        ];
+        let (from, to) = (coordinate!(0, 0), coordinate!(100, 30));
+        let route = orthogonal(from, to, &obstacles);
+        assert_eq!(route.first(), Some(&from));
+        assert_eq!(route.last(), Some(&to));
+        assert!(route
+            .windows(2)
+            .all(|w| w[0].x == w[1].x || w[0].y == w[1].y));
+        assert!(clear(&route, &obstacles));
+    }
+
+    #[test]
+    fn ignores_obstacles_at_the_ends() {
+        let obstacles = [(coordinate!(-5, -5), coordinate!(5, 5))];
+        let route = orthogonal(coordinate!(0, 0), coordinate!(50, 0), &obstacles);
+        assert_eq!(route, vec![coordinate!(0, 0), coordinate!(50, 0)]);
+    }
+}
@@ -0,0 +1,434 @@
+/*!
+Vector output for the Map struct.
+
+Elements are written as SVG elements instead of being rasterized, so the output can be scaled without losing detail.
+ */
+
+use super::{
+    super::*,
+    label::{self, Labels, Placement},
+    theme::{Paint, Theme},
+    Viewport,
+};
+use image::Rgba;
+use std::fmt::{self, Write};
+
+/**
+A SVG document that Draw implementations write elements to.
+ */
+#[derive(Clone, Debug, Default)]
+pub struct Svg {
+    width: u32,
+    height: u32,
+    viewport: Viewport,
+    theme: Theme,
+    labels: Labels,
+    obstacles: Vec<(Coordinate, Coordinate)>,
+    sizes: HashMap<u64, u32>,
+    gradients: usize,
+    body: String,
+}
+
+impl Svg {
+    /**
+    Constructs an empty document using the default Theme, matching the raster canvas.
+     */
+    pub fn new(width: u32, height: u32) -> Self {
+        Svg::with_viewport(width, height, Viewport::default())
+    }
+
+    /**
+    Constructs an empty document which draws world positions through the viewport.
+     */
+    pub fn with_viewport(width: u32, height: u32, viewport: Viewport) -> Self {
+        Svg {
+            width,
+            height,
+            viewport,
+            theme: Theme::default(),
+            labels: Labels::default(),
+            obstacles: Vec::new(),
+            sizes: HashMap::new(),
+            gradients: 0,
+            body: String::new(),
+        }
+    }
+
+    /**
+    Sets the Theme used for the background, and by elements drawn on the document.
+     */
+    pub fn with_theme(mut self, theme: Theme) -> Self {
+        self.theme = theme;
+        self
+    }
+
+    /**
+    Sets which side of the elements labels are preferably drawn on.
+     */
+    pub fn with_labels(mut self, placement: Placement) -> Self {
+        self.labels = Labels::new(placement);
+        self
+    }
+
+    /**
+    Returns the Theme elements are drawn with.
+     */
+    pub fn theme(&self) -> &Theme {
+        &self.theme
+    }
+
+    /**
+    Marks the area between min and max as taken, so Orthogonal edges are routed around it.
+     */
+    pub fn avoid(&mut self, min: Coordinate, max: Coordinate) {
+        self.obstacles.push((min, max));
+    }
+
+    /**
+    Returns the areas Orthogonal edges are routed around.
+     */
+    pub fn obstacles(&self) -> &[(Coordinate, Coordinate)] {
+        &self.obstacles
+    }
+
+    /**
+    Records the size of the element with the hash, so arrowheads pointing at it stop at its edge.
+     */
+    pub fn sized(&mut self, hash: u64, size: u32) {
+        self.sizes.insert(hash, size);
+    }
+
+    /**
+    Returns the size of the element with the hash, or the default Node size if it has not been recorded.
+     */
+    pub fn size_of(&self, hash: u64) -> u32 {
+        self.sizes
+            .get(&hash)
+            .copied()
+            .unwrap_or_else(|| u32::from(consts::DEFAULT_SIZE))
+    }
+
+    /**
+    Returns the position in the document the world position is drawn at.
+     */
+    pub fn project(&self, p: Point) -> Coordinate {
+        self.viewport.project(p)
+    }
+
+    /**
+    Returns a coordinate with the width and height of the document.
+     */
+    pub fn dimensions(&self) -> Coordinate {
+        coordinate!(self.width, self.height)
+    }
+
+    /**
+    Appends a raw element to the document.
+     */
+    pub fn push(&mut self, element: &str) {
+        self.body.push_str(element);
+        self.body.push('\n');
+    }
+
+    /**
+    Opens a layer which all following elements are part of, until group_end is called.
+     */
+    pub fn group_start(&mut self, hash: u64) {
+        self.push(&format!("<g id=\"g{}\">", hash));
+    }
+
+    /**
+    Closes the last opened layer.
+     */
+    pub fn group_end(&mut self) {
+        self.push("</g>");
+    }
+
+    /**
+    Adds a shape with the top-left corner at the position, following the same layout as Shape::area.
+     */
+    pub fn shape(&mut self, shape: &Shape, pos: Coordinate, size: u32, color: Rgba<u8>) {
+        let fill = tools::to_hex(color);
+        let element = match shape {
+            Shape::Circle => format!(
+                "<circle cx=\"{}\" cy=\"{}\" r=\"{}\" fill=\"{}\"/>",
+                pos.x,
+                pos.y,
+                size.saturating_sub(1),
+                fill
+            ),
+            Shape::Square => format!(
+                "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"{}\"/>",
+                pos.x, pos.y, size, size, fill
+            ),
+            Shape::Triangle => format!(
+                "<polygon points=\"{}\" fill=\"{}\"/>",
+                points(&[
+                    pos + coordinate!(size / 2, 0),
+                    pos + coordinate!(0, size),
+                    pos + coordinate!(size, size),
+                ]),
+                fill
+            ),
+            Shape::Ring => {
+                let outer = f64::from(size) - 0.5;
+                format!(
+                    "<circle cx=\"{}\" cy=\"{}\" r=\"{}\" fill=\"none\" stroke=\"{}\" stroke-width=\"{}\"/>",
+                    pos.x,
+                    pos.y,
+                    outer * 0.75,
+                    fill,
+                    outer / 2.0
+                )
+            }
+            Shape::Diamond | Shape::Hexagon | Shape::Star => format!(
+                "<polygon points=\"{}\" fill=\"{}\"/>",
+                shape
+                    .corners(f64::from(size))
+                    .unwrap_or_default()
+                    .iter()
+                    .fold(String::new(), |mut acc, c| {
+                        if !acc.is_empty() {
+                            acc.push(' ');
+                        }
+                        let round = |v: f64| (v * 100.0).round() / 100.0;
+                        let _ = write!(
+                            acc,
+                            "{},{}",
+                            round(f64::from(pos.x) + c.x),
+                            round(f64::from(pos.y) + c.y)
+                        );
+                        acc
+                    }),
+                fill
+            ),
+            Shape::Custom(custom) => return custom.draw_svg(self, pos, size, color),
+        };
+        self.push(&element);
+    }
+
+    /**
+    Adds a filled polygon with the corners.
+     */
+    pub fn polygon(&mut self, corners: &[Coordinate], color: Rgba<u8>) {
+        self.push(&format!(
+            "<polygon points=\"{}\" fill=\"{}\"/>",
+            points(corners),
+            tools::to_hex(color)
+        ));
+    }
+
+    /**
+    Adds the text next to the area between min and max, where it does not overlap other labels.
+
+    Labels are placed as if drawn with the bitmap font, so they land in the same spot as on a raster Map.
+     */
+    pub fn label(&mut self, min: Coordinate, max: Coordinate, text: &str) {
+        let size = label::size(text);
+        let pos = match self.labels.place(min, max, size, self.dimensions()) {
+            Some(pos) => pos,
+            None => return,
+        };
+        self.push(&format!(
+            "<text x=\"{}\" y=\"{}\" font-family=\"monospace\" font-size=\"{}\" fill=\"{}\">{}</text>",
+            pos.x,
+            pos.y + size.y,
+            size.y + 2,
+            tools::to_hex(self.theme.label),
+            escape(text)
+        ));
+    }
+
+    /**
+    Adds a line through all the points. A gradient goes from the first to the last point.
+     */
+    pub fn polyline(&mut self, line: &[Coordinate], paint: Paint, width: u32, pattern: Stroke) {
+        let stroke = self.stroke(line, paint);
+        let dash = match pattern.pattern(width) {
+            Some((on, off)) => format!(" stroke-dasharray=\"{} {}\"", on, off),
+            None => String::new(),
+        };
+        let element = match line {
+            [from, to] => format!(
+                "<line x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\" stroke=\"{}\" stroke-width=\"{}\"{}/>",
+                from.x, from.y, to.x, to.y, stroke, width, dash
+            ),
+            _ => format!(
+                "<path d=\"M {}\" fill=\"none\" stroke=\"{}\" stroke-width=\"{}\"{}/>",
+                points(line).replace(' ', " L "),
+                stroke,
+                width,
+                dash
+            ),
+        };
+        self.push(&element);
+    }
+
+    /**
+    Returns the stroke attribute value for the paint, defining a gradient along the line if needed.
+     */
+    fn stroke(&mut self, line: &[Coordinate], paint: Paint) -> String {
+        let (from, to, a, b) = match (paint, line.first(), line.last()) {
+            (Paint::Gradient(from, to), Some(a), Some(b)) => (from, to, a, b),
+            (paint, ..) => return tools::to_hex(paint.at(0.0)),
+        };
+        self.gradients += 1;
+        let id = format!("e{}", self.gradients);
+        self.push(&format!(
+            "<linearGradient id=\"{}\" gradientUnits=\"userSpaceOnUse\" x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\">\
+             <stop offset=\"0\" stop-color=\"{}\"/><stop offset=\"1\" stop-color=\"{}\"/></linearGradient>",
+            id,
+            a.x,
+            a.y,
+            b.x,
+            b.y,
+            tools::to_hex(from),
+            tools::to_hex(to)
+        ));
+        format!("url(#{})", id)
+    }
+}
+
+/**
+Escapes the characters which have a meaning in SVG text.
+ */
+fn escape(text: &str) -> String {
+    text.chars().fold(String::new(), |mut acc, c| {
+        match c {
+            '&' => acc.push_str("&amp;"),
+            '<' => acc.push_str("&lt;"),
+            '>' => acc.push_str("&gt;"),
+            '"' => acc.push_str("&quot;"),
+            c => acc.push(c),
+        }
+        acc
+    })
+}
+
+/**
+Formats the points as a list of x,y pairs.
+ */
+fn points(list: &[Coordinate]) -> String {
+    list.iter().fold(String::new(), |mut acc, p| {
+        if !acc.is_empty() {
+            acc.push(' ');
+        }
+        let _ = write!(acc, "{},{}", p.x, p.y);
+        acc
+    })
+}
+
+impl fmt::Display for Svg {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\">",
+            w = self.width,
+            h = self.height
+        )?;
+        writeln!(
+            f,
+            "<rect width=\"100%\" height=\"100%\" fill=\"{}\"/>",
+            tools::to_hex(self.theme.background)
+        )?;
+        write!(f, "{}", self.body)?;
+        writeln!(f, "</svg>")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_document() {
+        let svg = Svg::new(20, 10).to_string();
+        assert!(svg.starts_with("<svg"));
+        assert!(svg.contains("width=\"20\" height=\"10\""));
+        assert!(svg.trim_end().ends_with("</svg>"));
+    }
+
+    #[test]
+    fn shapes() {
+        let mut svg = Svg::new(20, 20);
+        let red = Rgba([255, 0, 0, 255]);
+        svg.shape(&Shape::Circle, coordinate!(5), 4, red);
+        svg.shape(&Shape::Square, coordinate!(5), 4, red);
+        svg.shape(&Shape::Triangle, coordinate!(5), 4, red);
+        svg.shape(&Shape::Diamond, coordinate!(5), 4, red);
+        svg.shape(&Shape::Ring, coordinate!(5), 4, red);
+        let svg = svg.to_string();
+        assert!(svg.contains("<polygon points=\"7,5 9,7 7,9 5,7\""));
+        assert!(svg.contains("r=\"2.625\" fill=\"none\" stroke=\"#ff0000\" stroke-width=\"1.75\""));
+        assert!(svg.contains("<circle cx=\"5\" cy=\"5\" r=\"3\" fill=\"#ff0000\"/>"));
+        assert!(svg.contains("<rect x=\"5\" y=\"5\" width=\"4\" height=\"4\""));
+        assert!(svg.contains("<polygon points=\"7,5 5,9 9,9\""));
+    }
+
+    #[test]
+    fn polylines() {
+        let mut svg = Svg::new(20, 20);
+        let grey = Rgba([40, 40, 40, 255]);
+        svg.polyline(
+            &Coordinate::from_list(&[(0, 0), (10, 10)]),
+            grey.into(),
+            2,
+            Stroke::Solid,
+        );
+        svg.polyline(
+            &Coordinate::from_list(&[(0, 0), (10, 0), (10, 10)]),
+            Paint::Gradient(grey, Rgba([255, 0, 0, 255])),
+            2,
+            Stroke::Dashed,
+        );
+        let svg = svg.to_string();
+        assert!(svg.contains("<line x1=\"0\" y1=\"0\" x2=\"10\" y2=\"10\" stroke=\"#282828\""));
+        assert!(svg.contains("<path d=\"M 0,0 L 10,0 L 10,10\" fill=\"none\" stroke=\"url(#e1)\" stroke-width=\"2\" stroke-dasharray=\"8 4\"/>"));
+        assert!(svg.contains("<linearGradient id=\"e1\" gradientUnits=\"userSpaceOnUse\" x1=\"0\" y1=\"0\" x2=\"10\" y2=\"10\">"));
+    }
+
+    #[test]
+    fn map_nodes() {
+        let mut nodes = Node::linked_list(Node::from_list(&[(0, 0), (50, 50), (100, 0)]));
+        nodes[1].hl_mut(0).unwrap().style(EdgeStyle::Straight);
+        nodes[2].hl_mut(0).unwrap().style(EdgeStyle::Ellipse);
+        let map = Map::new_svg().map(&nodes);
+        let svg = map.svg().unwrap();
+        assert_eq!(svg.dimensions(), coordinate!(110, 60));
+
+        let svg = svg.to_string();
+        assert_eq!(svg.matches("<rect").count(), 4);
+        assert_eq!(svg.matches("<path").count(), 2);
+    }
+
+    #[test]
+    fn labels() {
+        let mut svg = Svg::new(40, 40);
+        svg.label(coordinate!(10), coordinate!(14), "a<b");
+        svg.label(coordinate!(10), coordinate!(14), "c");
+        let svg = svg.to_string();
+        assert!(svg.contains("<text x=\"16\" y=\"16\" font-family=\"monospace\" font-size=\"9\" fill=\"#ffffff\">a&lt;b</text>"));
+        assert_eq!(svg.matches("<text").count(), 2);
+    }
+
+    #[test]
+    fn themed_background() {
+        let svg = Svg::new(10, 10).with_theme(Theme::light()).to_string();
+        assert!(svg.contains("<rect width=\"100%\" height=\"100%\" fill=\"#ffffff\"/>"));
+    }
+
+    #[test]
+    fn map_groups() {
+        let mut groups = Group::from_list(&[(0, 0), (100, 100)]);
+        for group in groups.iter_mut() {
+            group.add(10);
+        }
+        let svg = Map::new_svg()
+            .map_shape(&groups, &Shape::Circle)
+            .svg()
+            .unwrap()
+            .to_string();
+        assert_eq!(svg.matches("<g id=").count(), 2);
+        assert_eq!(svg.matches("<circle").count(), 20);
+    }
+}
@@ -0,0 +1,198 @@
+/*!
+Colors and sizes used when drawing a Map.
+
+A Theme replaces the defaults in consts, so the same elements can be drawn in light mode or in brand colors.
+ */
+
+use super::super::*;
+use image::Rgba;
+
+/**
+How an edge is colored.
+
+
+## Solid
+
+The whole edge has the same color.
+
+
+## Gradient
+
+The color changes from the first to the second, going from the start to the end of the edge.
+ */
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Paint {
+    Solid(Rgba<u8>),
+    Gradient(Rgba<u8>, Rgba<u8>),
+}
+
+impl Paint {
+    /**
+    Returns the color at progress t along the edge, between 0 and 1.
+
+
+    ## Examples
+
+    ```
+    # use pathtracer::map::theme::Paint;
+    let paint = Paint::Gradient(image::Rgba([0, 0, 0, 255]), image::Rgba([200, 100, 0, 255]));
+    assert_eq!(paint.at(0.5).0, [100, 50, 0, 255]);
+    ```
+     */
+    pub fn at(&self, t: f64) -> Rgba<u8> {
+        match *self {
+            Paint::Solid(color) => color,
+            Paint::Gradient(from, to) => {
+                let t = t.clamp(0.0, 1.0);
+                let mut color = from;
+                for (c, (a, b)) in color.0.iter_mut().zip(from.0.iter().zip(to.0.iter())) {
+                    *c = (f64::from(*a) * (1.0 - t) + f64::from(*b) * t).round() as u8;
+                }
+                color
+            }
+        }
+    }
+
+    /**
+    Returns the color at the point p, by how far along the line from a to b it is.
+     */
+    pub fn along(&self, a: Point, b: Point, p: Point) -> Rgba<u8> {
+        let ab = b - a;
+        let length = ab.x * ab.x + ab.y * ab.y;
+        if length == 0.0 {
+            return self.at(0.0);
+        }
+        self.at(((p.x - a.x) * ab.x + (p.y - a.y) * ab.y) / length)
+    }
+}
+
+impl From<Rgba<u8>> for Paint {
+    fn from(color: Rgba<u8>) -> Self {
+        Paint::Solid(color)
+    }
+}
+
+/**
+The colors and sizes a Map draws with.
+
+
+## Edges
+
+Without an edge Paint, edges are drawn in greys which get lighter by shade for each pixel of width.
+
+
+## Shade
+
+Added to each color channel of the outline of Nodes, and used for the edge greys.
+ */
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Theme {
+    pub background: Rgba<u8>,
+    pub node: Rgba<u8>,
+    pub edge: Option<Paint>,
+    pub label: Rgba<u8>,
+    pub shade: u8,
+    pub link_width: u32,
+}
+
+impl Default for Theme {
+    /**
+    A black background with black Nodes, grey edges and white labels.
+     */
+    fn default() -> Self {
+        Theme {
+            background: consts::DEFAULT_RGBA,
+            node: consts::DEFAULT_RGBA,
+            edge: None,
+            label: Rgba([255, 255, 255, 255]),
+            shade: consts::DEFAULT_SHADE as u8,
+            link_width: u32::from(consts::DEFAULT_LINK_SIZE),
+        }
+    }
+}
+
+impl Theme {
+    /**
+    A white background with dark grey Nodes, light grey edges and black labels.
+
+
+    ## Examples
+
+    ```
+    # use pathtracer::{map::theme::Theme, *};
+    let image = Map::new().theme(Theme::light()).map(&Node::from_list(&[(0, 0), (20, 20)])).consume();
+    assert_eq!(image.image().get_pixel(0, 0).0, [255, 255, 255, 255]);
+    ```
+     */
+    pub fn light() -> Self {
+        Theme {
+            background: Rgba([255, 255, 255, 255]),
+            node: Rgba([40, 40, 40, 255]),
+            edge: Some(Paint::Solid(Rgba([170, 170, 170, 255]))),
+            label: Rgba([0, 0, 0, 255]),
+            ..Theme::default()
+        }
+    }
+
+    /**
+    Returns the outline color of a Node with the given color.
+     */
+    pub fn outline(&self, color: Rgba<u8>) -> Rgba<u8> {
+        let mut outline = color;
+        for c in outline.0.iter_mut().take(3) {
+            *c = c.saturating_add(self.shade);
+        }
+        outline
+    }
+
+    /**
+    Returns the Paint of an edge with the given width, shaded grey if the Theme has no edge Paint.
+     */
+    pub fn edge(&self, width: u32) -> Paint {
+        self.edge.unwrap_or_else(|| {
+            let shade = std::cmp::min(width.saturating_mul(u32::from(self.shade)), 255) as u8;
+            Paint::Solid(Rgba([shade, shade, shade, 255]))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gradient_along() {
+        let paint = Paint::Gradient(Rgba([0, 0, 0, 0]), Rgba([100, 100, 100, 100]));
+        let (a, b) = (Point::new(0.0, 0.0), Point::new(10.0, 0.0));
+        assert_eq!(paint.along(a, b, Point::new(-5.0, 3.0)).0, [0, 0, 0, 0]);
+        assert_eq!(paint.along(a, b, Point::new(3.0, 3.0)).0, [30, 30, 30, 30]);
+        assert_eq!(paint.along(a, b, Point::new(20.0, 0.0)).0, [100; 4]);
+        assert_eq!(paint.along(a, a, a).0, [0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn default_edges_are_shaded() {
+        let theme = Theme::default();
+        assert_eq!(theme.edge(2), Paint::Solid(Rgba([40, 40, 40, 255])));
+        assert_eq!(theme.edge(100), Paint::Solid(Rgba([255, 255, 255, 255])));
+        assert_eq!(
+            theme.edge(u32::MAX),
+            Paint::Solid(Rgba([255, 255, 255, 255]))
+        );
+        let red = Paint::Solid(Rgba([255, 0, 0, 255]));
+        let theme = Theme {
+            edge: Some(red),
+            ..theme
+        };
+        assert_eq!(theme.edge(2), red);
+    }
+
+    #[test]
+    fn outline() {
+        let theme = Theme {
+            shade: 50,
+            ..Theme::default()
+        };
+        assert_eq!(theme.outline(Rgba([10, 250, 0, 128])).0, [60, 255, 50, 128]);
+    }
+}
@@ -0,0 +1,345 @@
+/*!
+Reads Nodes from comma separated values.
+
+Each record is a line with the columns x, y, name, color, radius and label, where only x and y are required.
+
+A header naming the columns can be provided as the first record, in which case the columns may come in any order.
+Lines starting with the comment character, and empty lines, are ignored.
+
+```
+# use pathtracer::node::csv;
+let content = "# Cities\nname,x,y,color\nA,0,0,#ff0000\nB,10,20,\n";
+let report = csv::parse(content, &csv::Options::default()).unwrap();
+assert_eq!(report.nodes.len(), 2);
+assert_eq!(report.nodes[0].color, Some(image::Rgba([255, 0, 0, 255])));
+assert_eq!(report.nodes[1].color, None);
+```
+ */
+
+use super::super::*;
+use std::{fs::File, io::prelude::*};
+
+/**
+Configures how the content is interpreted.
+
+
+## Lenient
+
+When lenient, invalid lines are skipped and reported as warnings instead of failing the whole parse.
+ */
+#[derive(Clone, Debug)]
+pub struct Options {
+    pub separator: char,
+    pub comment: char,
+    pub lenient: bool,
+}
+
+/**
+The Nodes which were read, along with the problems found on skipped lines.
+ */
+#[derive(Debug, Default)]
+pub struct Report {
+    pub nodes: Vec<Node>,
+    pub warnings: Vec<Error>,
+}
+
+/**
+The column positions of a record.
+ */
+#[derive(Clone, Copy)]
+struct Columns {
+    x: usize,
+    y: usize,
+    name: Option<usize>,
+    color: Option<usize>,
+    radius: Option<usize>,
+    label: Option<usize>,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            separator: ',',
+            comment: '#',
+            lenient: false,
+        }
+    }
+}
+
+impl Options {
+    /**
+    Default options which skip invalid lines rather than failing.
+     */
+    pub fn lenient() -> Self {
+        Options {
+            lenient: true,
+            ..Options::default()
+        }
+    }
+}
+
+impl Default for Columns {
+    fn default() -> Self {
+        Columns {
+            x: 0,
+            y: 1,
+            name: Some(2),
+            color: Some(3),
+            radius: Some(4),
+            label: Some(5),
+        }
+    }
+}
+
+impl Columns {
+    /**
+    Reads the column positions from a header, returns None if the record is not a header.
+     */
+    fn from_header(fields: &[&str]) -> Option<Self> {
+        let find = |name: &str| fields.iter().position(|f| f.eq_ignore_ascii_case(name));
+        Some(Columns {
+            x: find("x")?,
+            y: find("y")?,
+            name: find("name"),
+            color: find("color"),
+            radius: find("radius"),
+            label: find("label"),
+        })
+    }
+}
+
+/**
+Reads a file and parses it's content.
+
+
+## Errors
+
+Could not open or read the file.
+
+Error::ParseError for the first invalid line, unless the options are lenient.
+ */
+pub fn read(path: &str, options: &Options) -> Result<Report, Error> {
+    let mut contents = String::new();
+    File::open(path)?.read_to_string(&mut contents)?;
+    parse(&contents, options)
+}
+
+/**
+Parses Nodes from the content.
+
+
+## Examples
+
+Invalid lines can be skipped and reviewed afterwards.
+
+```
+# use pathtracer::node::csv;
+let content = "0,0\n10,ten\n20,20";
+assert!(csv::parse(content, &csv::Options::default()).is_err());
+
+let report = csv::parse(content, &csv::Options::lenient()).unwrap();
+assert_eq!(report.nodes.len(), 2);
+assert_eq!(report.warnings.len(), 1);
+```
+
+
+## Errors
+
+Error::ParseError for the first invalid line, unless the options are lenient.
+ */
+pub fn parse(content: &str, options: &Options) -> Result<Report, Error> {
+    let mut report = Report::default();
+    let mut columns: Option<Columns> = None;
+
+    for (i, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with(options.comment) {
+            continue;
+        }
+
+        let fields = line
+            .split(options.separator)
+            .map(|f| f.trim())
+            .collect::<Vec<_>>();
+
+        // Only the first record may be a header.
+        let cols = match columns {
+            Some(cols) => cols,
+            None => {
+                let header = Columns::from_header(&fields);
+                columns = Some(header.unwrap_or_default());
+                if header.is_some() {
+                    continue;
+                }
+                Columns::default()
+            }
+        };
+
+        match record(&fields, cols) {
+            Ok(node) => report.nodes.push(node),
+            Err(reason) => {
+                let e = Error::ParseError {
+                    line: i + 1,
+                    reason,
+                };
+                if !options.lenient {
+                    return Err(e);
+                }
+                warn!("{}", e);
+                report.warnings.push(e);
+            }
+        }
+    }
+
+    Ok(report)
+}
+
+/**
+Constructs a Node from the fields of a record.
+ */
+fn record(fields: &[&str], cols: Columns) -> Result<Node, String> {
+    let field = |i: Option<usize>| i.and_then(|i| fields.get(i)).filter(|f| !f.is_empty());
+
+    let (x, y) = match (field(Some(cols.x)), field(Some(cols.y))) {
+        (Some(x), Some(y)) => (*x, *y),
+        _ => {
+            return Err(format!(
+                "expected at least 'x,y', found '{}'",
+                fields.join(",")
+            ))
+        }
+    };
+
+    let geo = Point::new(coordinate(x)?, coordinate(y)?);
+    let name = match field(cols.name) {
+        Some(name) => name.to_string(),
+        None => format!("{},{}", x, y),
+    };
+
+    let mut node = Node::new(&name, geo);
+    if let Some(c) = field(cols.color) {
+        node.color = tools::from_hex(c)
+            .map(Some)
+            .ok_or_else(|| format!("invalid color '{}', expected #rrggbb or #rrggbbaa", c))?;
+    }
+    if let Some(r) = field(cols.radius) {
+        node.radius = Some(
+            r.parse()
+                .map_err(|e| format!("invalid radius '{}': {}", r, e))?,
+        );
+    }
+    if let Some(label) = field(cols.label) {
+        node.label(label);
+    }
+    Ok(node)
+}
+
+/**
+Parses a coordinate value, decimals are kept.
+ */
+fn coordinate(value: &str) -> Result<f64, String> {
+    let v = value
+        .parse::<f64>()
+        .map_err(|e| format!("invalid coordinate '{}': {}", value, e))?;
+    if v.is_finite() {
+        Ok(v)
+    } else {
+        Err(format!("coordinate '{}' is out of range", value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strict(content: &str) -> Result<Report, Error> {
+        parse(content, &Options::default())
+    }
+
+    #[test]
+    fn plain_coordinates() {
+        let report = strict("100,20\n40,60\n\n30,30\n").unwrap();
+        let geos = report
+            .nodes
+            .iter()
+            .map(|n| n.position())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            geos,
+            Coordinate::from_list(&[(100, 20), (40, 60), (30, 30)])
+        );
+        assert_eq!(report.nodes[0], node!(100, 20));
+        assert!(report.warnings.is_empty());
+    }
+
+    #[test]
+    fn optional_columns() {
+        let report = strict("0,0,A,#0a0b0c,7,Start\n1,1,,,\n2.4,-2.6,B").unwrap();
+        let n = &report.nodes;
+        assert_eq!(n[0], node!("A", 0, 0));
+        assert_eq!(n[0].color, Some(image::Rgba([10, 11, 12, 255])));
+        assert_eq!(n[0].radius, Some(7));
+        assert_eq!(n[0].label.as_deref(), Some("Start"));
+        assert_eq!(n[1], node!(1, 1));
+        assert_eq!(n[1].radius, None);
+        assert_eq!(n[1].label, None);
+        assert_eq!(n[2].geo, Point::new(2.4, -2.6));
+        assert_eq!(n[2].position(), Coordinate::new(2, -3));
+    }
+
+    #[test]
+    fn header_and_comments() {
+        let content = "# exported nodes\nRadius, Name, Y, X\n# first\n5, A, 10, 20\n";
+        let report = strict(content).unwrap();
+        assert_eq!(report.nodes.len(), 1);
+        assert_eq!(report.nodes[0], node!("A", 20, 10));
+        assert_eq!(report.nodes[0].radius, Some(5));
+    }
+
+    #[test]
+    fn custom_separator() {
+        let options = Options {
+            separator: ';',
+            ..Options::default()
+        };
+        let report = parse("1;2\n3;4", &options).unwrap();
+        assert_eq!(report.nodes.len(), 2);
+    }
+
+    #[test]
+    fn line_numbers() {
+        let cases = [
+            ("0,0\n1\n", 2),
+            ("0,0\n\n# c\nx,1\n", 4),
+            ("0,0,A,#ff00zz", 1),
+            ("0,0,A,#ff0000,-1", 1),
+            ("inf,0", 1),
+        ];
+        for (content, expected) in cases.iter() {
+            match strict(content) {
+                Err(Error::ParseError { line, .. }) => assert_eq!(line, *expected, "{}", content),
+                e => panic!("expected ParseError for '{}', got {:?}", content, e),
+            }
+        }
+    }
+
+    #[test]
+    fn lenient_collects_warnings() {
+        let report = parse("0,0\nfoo\n1,1\n2,bar\n", &Options::lenient()).unwrap();
+        assert_eq!(report.nodes.len(), 2);
+        let lines = report
+            .warnings
+            .iter()
+            .map(|e| match e {
+                Error::ParseError { line, .. } => *line,
+                _ => 0,
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(lines, vec![2, 4]);
+    }
+
+    #[test]
+    fn read_missing_file() {
+        assert!(read("invalid path", &Options::default()).is_err());
+    }
+}
@@ -3,13 +3,11 @@ Extra functionality for Nodes.
 */
 
 use super::*;
-use std::{
-    cmp::PartialEq,
-    fs::File,
-    io::{self, prelude::*},
-};
+use std::{cmp::PartialEq, fs::File, io::prelude::*};
 
-impl<'a> PartialEq for Node {
+pub mod csv;
+
+impl PartialEq for Node {
     fn eq(&self, other: &Node) -> bool {
         self.hash == other.hash
     }
@@ -24,7 +22,7 @@ Returns a list of Strings split using \n in a Vec.
 Could not open file.
 Could not read content to string.
 */
-pub fn get_node_names(path: &str) -> Result<Vec<String>, io::Error> {
+pub fn get_node_names(path: &str) -> Result<Vec<String>, Error> {
     let mut file = File::open(path)?;
     let mut contents = String::new();
     file.read_to_string(&mut contents)?;
@@ -43,7 +41,7 @@ Write the positions to a file.
 Could not open file.
 Could not write to file.
  */
-pub fn write_file(path: &str, nodes: &[Node]) -> std::io::Result<()> {
+pub fn write_file(path: &str, nodes: &[Node]) -> Result<(), Error> {
     let mut file = File::create(path)?;
     for n in nodes.iter() {
         let p = n.position();
@@ -56,41 +54,22 @@ pub fn write_file(path: &str, nodes: &[Node]) -> std::io::Result<()> {
 Reads the positions from a file.
 
 
-Expects eact line format to be:
+Expects each line format to be:
 
 x,y
 
+Optional name, color and radius columns, headers and comments are supported as described in the csv module.
+
 
 ## Errors
 
 Could not open file.
 Could not read to string.
+Error::ParseError if a line is not in the expected format.
 
 */
-pub fn from_file(path: &str) -> Result<Vec<Node>, io::Error> {
-    let mut contents = String::new();
-    let mut file = File::open(path)?;
-    file.read_to_string(&mut contents)?;
-    Ok(contents.split('\n').fold(vec![], |mut acc, x| {
-        // Ignore empty lines, mostly occurs as file's tend to end with an empty line.
-        // Not that the protocal supports --- value, <blank line>, value --- explicity.
-        if x.is_empty() {
-            return acc;
-        }
-        let vals = x.split(',').collect::<Vec<_>>();
-        if vals.len() != 2 {
-            panic!(
-                "Failed to decode values from file: '{}', invalid line: '{}'",
-                path, x
-            );
-        }
-        let c = Coordinate::new(
-            vals[0].parse::<i16>().unwrap(),
-            vals[1].parse::<i16>().unwrap(),
-        );
-        acc.push(Node::new(x, c));
-        acc
-    }))
+pub fn from_file(path: &str) -> Result<Vec<Node>, Error> {
+    Ok(csv::read(path, &csv::Options::default())?.nodes)
 }
 
 /**
@@ -136,4 +115,14 @@ mod tests {
         let res = get_node_names("invalid path");
         assert!(res.is_err());
     }
+
+    #[test]
+    fn test_from_file_invalid_line() {
+        let path = "/tmp/pathtracer_from_file_invalid_line.csv";
+        std::fs::write(path, "1,2\n3,4\n5;6\n").unwrap();
+        match from_file(path) {
+            Err(Error::ParseError { line, .. }) => assert_eq!(line, 3),
+            e => panic!("expected ParseError, got {:?}", e),
+        }
+    }
 }
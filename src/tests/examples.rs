@@ -9,7 +9,7 @@ fn city() {
     for y in 0..city_size / 2 {
         for x in 0..city_size * 2 {
             let mut node = node!(spread * x, spread * y);
-            node.color = tools::seed_rgb((city_size * x + spread * y) as u64);
+            node.color = Some(tools::seed_rgb((city_size * x + spread * y) as u64));
             pos.push(node);
         }
     }
@@ -78,7 +78,7 @@ fn ellipse() {
 
 #[test]
 fn cycles() -> std::io::Result<()> {
-    let mut gif = map::gif::Gif::new("/tmp/out.gif", 50, 50);
+    let mut gif = map::gif::Gif::new("/tmp/out.gif", 50, 50)?;
     let balls = Node::from_list(&[(20, 20), (30, 30), (40, 40)]);
 
     gif.cycle(2, balls);
@@ -97,9 +97,9 @@ fn graph() {
 
     let mut pos = Vec::new();
     let y = [5, 30, 45, 35, 40, 80, 75, 70, 25, 30];
-    let spread = 300 / (y.len() - 1) as i16;
+    let spread = 300 / (y.len() - 1) as i32;
     for (i, y) in y.iter().enumerate() {
-        pos.push((i as i16 * spread, -*y));
+        pos.push((i as i32 * spread, -*y));
     }
     let line = Node::linked_list(Node::from_list(&pos));
 
@@ -5,6 +5,20 @@ mod from_list;
 #[cfg(test)]
 mod integration {
 
+    mod send {
+        use crate::*;
+
+        fn is_send<T: Send>() {}
+
+        #[test]
+        fn graphs_are_send() {
+            is_send::<Node>();
+            is_send::<Group>();
+            is_send::<Network<Node>>();
+            is_send::<Map>();
+        }
+    }
+
     mod rotate {
         use crate::*;
 
@@ -26,7 +40,7 @@ mod integration {
             g
         }
 
-        fn no_move(a: &Vec<Node>, b: &Vec<Node>) {
+        fn no_move(a: &[Node], b: &[Node]) {
             let matching = a
                 .iter()
                 .zip(b.iter())
@@ -105,34 +119,26 @@ mod integration {
         }
 
         #[test]
-        fn max_links() {
+        fn many_links() {
             let mut a = Node::new("A", Coordinate::new(0, 0));
-            let nodes = Node::linked_list(Node::from_list(&[
-                (0, 0),
-                (1, 1),
-                (2, 2),
-                (3, 3),
-                (4, 4),
-                (5, 5),
-                (6, 6),
-                (7, 7),
-                (8, 8),
-                (9, 9),
-            ]));
+            let list = (1..100).map(|i| (i, i)).collect::<Vec<_>>();
+            let nodes = Node::linked_list(Node::from_list(&list));
 
             for (i, node) in nodes.iter().enumerate() {
                 a.link(node);
-                if i < 5 {
-                    assert!(
-                        a.hl(i).is_ok(),
-                        "expected {}, got {}",
-                        i,
-                        a.get_link_avail_index()
-                    );
-                } else {
-                    assert!(a.hl(i).is_err(), "Exceeding max_link should return Err");
-                }
+                assert!(
+                    a.hl(i).is_ok(),
+                    "expected {}, got {}",
+                    i,
+                    a.get_link_avail_index()
+                );
+                assert!(a.is_directly_connected(node));
             }
+            assert_eq!(a.links().len(), nodes.len());
+            assert!(a.hl(nodes.len()).is_err());
+
+            a.disconnect();
+            assert!(a.links().is_empty());
         }
 
         #[test]
@@ -5,8 +5,8 @@ Utility functions
 extern crate image;
 extern crate rand;
 
-use super::{Coordinate, Hash};
-use image::Rgb;
+use super::{Coordinate, Hash, Point};
+use image::Rgba;
 use rand::{distributions::Uniform, Rng};
 
 use std::{
@@ -23,7 +23,9 @@ pub fn find<T: Hash>(element: u64, list: &[T]) -> Option<&T> {
 }
 
 /**
-Returns a Rgb with a modified value depending on how close it is to it's falloff point.
+Returns a Rgba with a modified value depending on how close it is to it's falloff point.
+
+The alpha channel is kept as is.
 
 
 ## Examples
@@ -34,8 +36,8 @@ First declare the colors and the range at which it becomes darker.
 extern crate image;
 use pathtracer::{tools, Coordinate};
 let falloff = 100;
-let color = image::Rgb {
-     0: [100, 100, 100],
+let color = image::Rgba {
+     0: [100, 100, 100, 255],
 };
 ```
 
@@ -45,22 +47,22 @@ Evaluate that based on the modified distance, in the small sense it is modified
 # extern crate image;
 # use pathtracer::{tools, Coordinate};
 # let falloff = 100;
-# let color = image::Rgb([100, 100, 100]);
+# let color = image::Rgba([100, 100, 100, 255]);
 let base = Coordinate::new(0, 0);
 let to = Coordinate::new(10, 10);
 
 assert_eq!(
     tools::range_color(falloff, color, base, to),
-    image::Rgb([77, 77, 77])
+    image::Rgba([77, 77, 77, 255])
 );
 ```
 */
 pub fn range_color(
-    falloff: i16,
-    base: image::Rgb<u8>,
+    falloff: i32,
+    base: Rgba<u8>,
     base_geo: Coordinate,
     to_geo: Coordinate,
-) -> image::Rgb<u8> {
+) -> Rgba<u8> {
     let diff = (base_geo - to_geo).abs();
     let x_scale: f64 = f64::from(diff.x) / f64::from(falloff);
     let y_scale: f64 = f64::from(diff.y) / f64::from(falloff);
@@ -68,10 +70,11 @@ pub fn range_color(
         f64::from(i32::from(base[0]) + i32::from(base[1]) + i32::from(base[2]) / 3);
     let modify = (-max_multi * (x_scale + y_scale) / 2.0) as i32;
 
-    image::Rgb([
+    Rgba([
         border(base[0], modify),
         border(base[1], modify),
         border(base[2], modify),
+        base[3],
     ])
 }
 
@@ -88,7 +91,24 @@ assert!(nr >= 50 && nr <= 60);
 ```
  */
 pub fn roll<T: Into<u32>>(min: T, max: T) -> u32 {
-    let mut rng = rand::thread_rng();
+    roll_with(&mut rand::thread_rng(), min, max)
+}
+
+/**
+Returns a number between the min and maximum, drawn from the given random number generator.
+
+
+## Examples
+
+```
+# use pathtracer::tools;
+use rand::{rngs::StdRng, SeedableRng};
+let a = tools::roll_with(&mut StdRng::seed_from_u64(7), 0u32, 1000);
+let b = tools::roll_with(&mut StdRng::seed_from_u64(7), 0u32, 1000);
+assert_eq!(a, b);
+```
+ */
+pub fn roll_with<R: Rng + ?Sized, T: Into<u32>>(rng: &mut R, min: T, max: T) -> u32 {
     rng.sample(Uniform::new(min.into(), max.into()))
 }
 
@@ -119,7 +139,7 @@ pub fn border(a: u8, b: i32) -> u8 {
 }
 
 /**
-Returns a random Rgb color. the opacity is always 255.
+Returns a random Rgba color. the opacity is always 255.
 
 
 ## Examples
@@ -130,24 +150,80 @@ let rgb = tools::gen_rgb();
 println!("{:?}", rgb.0);
 ```
 */
-pub fn gen_rgb() -> Rgb<u8> {
-    // FIXME: this shouldn't hardcode number of channels
+pub fn gen_rgb() -> Rgba<u8> {
+    gen_rgb_with(&mut rand::thread_rng())
+}
+
+/**
+Returns a Rgba color drawn from the given random number generator. the opacity is always 255.
+*/
+pub fn gen_rgb_with<R: Rng + ?Sized>(rng: &mut R) -> Rgba<u8> {
+    // The last channel is the opacity, which is left as is.
     (0..3).fold(super::consts::DEFAULT_RGBA, |mut acc, x| {
-        acc.0[x] = acc.0[x].saturating_add(roll(0u8, u8::MAX).try_into().unwrap());
+        acc.0[x] = acc.0[x].saturating_add(roll_with(rng, 0u8, u8::MAX).try_into().unwrap());
         acc
     })
 }
 
 /**
-Returns a Rgb color based on a seed value. the opacity is always 255.
+Returns a Rgba color based on a seed value. the opacity is always 255.
 */
-pub fn seed_rgb(seed: u64) -> Rgb<u8> {
+pub fn seed_rgb(seed: u64) -> Rgba<u8> {
     let max = 254;
     let r = seed % max;
     let g = (seed + 75) % max;
     let b = (seed + 150) % max;
 
-    Rgb([r as u8, g as u8, b as u8])
+    Rgba([r as u8, g as u8, b as u8, 255])
+}
+
+/**
+Parses a color in the hex format #rrggbb or #rrggbbaa, the # is optional.
+
+Colors without an alpha channel are opaque.
+
+
+## Examples
+
+```
+# extern crate image;
+# use pathtracer::tools;
+assert_eq!(tools::from_hex("#ff8000"), Some(image::Rgba([255, 128, 0, 255])));
+assert_eq!(tools::from_hex("00ff0080"), Some(image::Rgba([0, 255, 0, 128])));
+assert_eq!(tools::from_hex("red"), None);
+```
+*/
+pub fn from_hex(value: &str) -> Option<Rgba<u8>> {
+    let hex = value.strip_prefix('#').unwrap_or(value);
+    if (hex.len() != 6 && hex.len() != 8) || !hex.is_ascii() {
+        return None;
+    }
+    let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+    let alpha = if hex.len() == 8 { channel(6)? } else { 255 };
+    Some(Rgba([channel(0)?, channel(2)?, channel(4)?, alpha]))
+}
+
+/**
+Formats a color in the hex format #rrggbb, or #rrggbbaa if it is not opaque.
+
+
+## Examples
+
+```
+# extern crate image;
+# use pathtracer::tools;
+assert_eq!(tools::to_hex(image::Rgba([255, 128, 0, 255])), "#ff8000");
+assert_eq!(tools::to_hex(image::Rgba([255, 128, 0, 16])), "#ff800010");
+```
+*/
+pub fn to_hex(color: Rgba<u8>) -> String {
+    let c = color.0;
+    let rgb = format!("#{:02x}{:02x}{:02x}", c[0], c[1], c[2]);
+    if c[3] == 255 {
+        rgb
+    } else {
+        format!("{}{:02x}", rgb, c[3])
+    }
 }
 
 /**
@@ -208,6 +284,62 @@ pub fn plot_type(
     }
 }
 
+/**
+Returns the pixels with their center inside the triangle between the three coordinates.
+
+
+## Examples
+
+```
+# use pathtracer::{tools, Coordinate};
+let plot = tools::plot_triangle(Coordinate::new(0, 0), Coordinate::new(4, 0), Coordinate::new(0, 4));
+assert!(plot.contains(&Coordinate::new(1, 1)));
+assert!(!plot.contains(&Coordinate::new(4, 4)));
+```
+*/
+pub fn plot_triangle(a: Coordinate, b: Coordinate, c: Coordinate) -> Vec<Coordinate> {
+    let edge = |p: Coordinate, q: Coordinate, r: Coordinate| {
+        i64::from(q.x - p.x) * i64::from(r.y - p.y) - i64::from(q.y - p.y) * i64::from(r.x - p.x)
+    };
+    let mut plot = Vec::new();
+    for x in min(a.x, min(b.x, c.x))..=max(a.x, max(b.x, c.x)) {
+        for y in min(a.y, min(b.y, c.y))..=max(a.y, max(b.y, c.y)) {
+            let p = Coordinate::new(x, y);
+            let (d1, d2, d3) = (edge(a, b, p), edge(b, c, p), edge(c, a, p));
+            if (d1 >= 0 && d2 >= 0 && d3 >= 0) || (d1 <= 0 && d2 <= 0 && d3 <= 0) {
+                plot.push(p);
+            }
+        }
+    }
+    plot
+}
+
+/**
+Returns true if the point is inside the polygon with the corners, using the even-odd rule.
+
+
+## Examples
+
+```
+# use pathtracer::{tools, Point};
+let square = [Point::new(0.0, 0.0), Point::new(4.0, 0.0), Point::new(4.0, 4.0), Point::new(0.0, 4.0)];
+assert!(tools::inside(&square, Point::new(1.0, 2.0)));
+assert!(!tools::inside(&square, Point::new(5.0, 2.0)));
+```
+*/
+pub fn inside(corners: &[Point], p: Point) -> bool {
+    let mut result = false;
+    let mut j = corners.len().wrapping_sub(1);
+    for (i, a) in corners.iter().enumerate() {
+        let b = corners[j];
+        if (a.y > p.y) != (b.y > p.y) && p.x < (b.x - a.x) * (p.y - a.y) / (b.y - a.y) + a.x {
+            result = !result;
+        }
+        j = i;
+    }
+    result
+}
+
 /**
 Draws a line between two coordinate points.
 Derived from: https://en.wikipedia.org/wiki/Bresenham%27s_line_algorithm
@@ -239,7 +371,7 @@ pub fn plot_bresenham(mut from: Coordinate, to: Coordinate) -> Vec<Coordinate> {
         last_y = from.y;
         error += delta_err;
         while error >= 0.50 {
-            from.y += f64::signum(delta_y) as i16;
+            from.y += f64::signum(delta_y) as i32;
             error -= 1.00;
         }
     }
@@ -304,12 +436,84 @@ Derived from: https://en.wikipedia.org/wiki/Ellipse
 
 This functionality is heavily a work in progress and it's behaviour is unreliable.
 */
-pub fn plot_ellipse(mut from: Coordinate, to: Coordinate) -> Vec<Coordinate> {
+pub fn plot_ellipse(from: Coordinate, to: Coordinate) -> Vec<Coordinate> {
+    let result = ellipse_points(from, to)
+        .windows(2)
+        .fold(vec![], |mut acc, w| {
+            acc.append(&mut plot_type(w[0], w[1], &plot_bresenham));
+            acc
+        });
+    debug!("{:#?}", result);
+    result
+}
+
+/**
+Returns points along a cubic Bezier curve from from to to, close enough to be drawn as straight lines between them.
+
+Both control points are moved to the left of the direction from from to to, by the curvature
+as a percentage of the distance between them. A negative curvature moves them to the right.
+
+
+## Examples
+
+```
+# use pathtracer::{tools, Coordinate};
+let a = Coordinate::new(0, 0);
+let b = Coordinate::new(100, 0);
+let points = tools::bezier_points(a, b, 50);
+assert_eq!(points.first(), Some(&a));
+assert_eq!(points.last(), Some(&b));
+// The middle of the curve is bowed by three quarters of the control points' offset.
+assert_eq!(points[points.len() / 2], Coordinate::new(50, -38));
+```
+*/
+pub fn bezier_points(from: Coordinate, to: Coordinate, curvature: i32) -> Vec<Coordinate> {
+    let (dx, dy) = (f64::from(to.x - from.x), f64::from(to.y - from.y));
+    let k = f64::from(curvature) / 100.0;
+    // The left normal in screen coordinates, where y grows downwards.
+    let (nx, ny) = (dy * k, -dx * k);
+    let p0 = (f64::from(from.x), f64::from(from.y));
+    let p3 = (f64::from(to.x), f64::from(to.y));
+    let p1 = (p0.0 + dx / 3.0 + nx, p0.1 + dy / 3.0 + ny);
+    let p2 = (p0.0 + dx * 2.0 / 3.0 + nx, p0.1 + dy * 2.0 / 3.0 + ny);
+
+    let steps = max(8, (dx.hypot(dy) / 8.0) as i32);
+    let mut result = (0..=steps)
+        .map(|i| {
+            let t = f64::from(i) / f64::from(steps);
+            let u = 1.0 - t;
+            let (a, b, c, d) = (u * u * u, 3.0 * u * u * t, 3.0 * u * t * t, t * t * t);
+            Coordinate::new(
+                (a * p0.0 + b * p1.0 + c * p2.0 + d * p3.0).round() as i32,
+                (a * p0.1 + b * p1.1 + c * p2.1 + d * p3.1).round() as i32,
+            )
+        })
+        .collect::<Vec<_>>();
+    result.dedup();
+    result
+}
+
+/**
+Returns the corners of the ellipse drawn by plot_ellipse, starting with from and ending with to.
+
+
+## Examples
+
+```
+# use pathtracer::{tools, Coordinate};
+let a = Coordinate::new(0, 0);
+let b = Coordinate::new(100, 50);
+let points = tools::ellipse_points(a, b);
+assert_eq!(points.first(), Some(&a));
+assert_eq!(points.last(), Some(&b));
+```
+*/
+pub fn ellipse_points(mut from: Coordinate, to: Coordinate) -> Vec<Coordinate> {
     let min = Coordinate::new(min(from.x, to.x), min(from.y, to.y));
     let _max = Coordinate::new(max(from.x, to.x), max(from.y, to.y));
     let _t_max = midpoint(from, to);
 
-    let mut result = Vec::new();
+    let mut result = vec![from];
     let t = f64::consts::PI / 2.0; // How the ellipse is angled. and how steep it angles.
     let mut theta: f64 = 0.5; // starting angle.
     let diff = from - to;
@@ -331,13 +535,11 @@ pub fn plot_ellipse(mut from: Coordinate, to: Coordinate) -> Vec<Coordinate> {
     while t > theta {
         let point = from + coordinate!(r * theta.cos(), f64::from(s.y) * r * theta.sin() / 2.0);
         debug!("Theta: {} | Coordinate: {} <- {}", theta, point, from);
-        let mut line = plot_type(from, point, &plot_bresenham);
-        result.append(&mut line);
+        result.push(point);
         from = point;
         theta += step;
     }
-    result.append(&mut plot_type(from, to, &plot_bresenham));
-    debug!("{:#?}", result);
+    result.push(to);
     result
 }
 
@@ -407,8 +609,8 @@ mod tests {
         let c1 = Coordinate::new(0, 0);
         let c2 = Coordinate::new(0, 10);
         let plot = plot(c1, c2);
-        for i in 0..10 {
-            assert_eq!(plot[i].y, i as i16);
+        for (i, c) in plot.iter().enumerate() {
+            assert_eq!(c.y, i as i32);
         }
     }
 }
@@ -11,6 +11,22 @@ pub trait Draw {
     fn draw(&self, image: IW, offset: Coordinate, shape: &Shape) -> IW;
     fn size(&self) -> u32;
     fn links(&self) -> &[HL];
+
+    /**
+    Writes the structure as vector elements.
+
+    Structures which do not implement it are left out of SVG output.
+     */
+    fn draw_svg(&self, _svg: &mut map::svg::Svg, _offset: Coordinate, _shape: &Shape) {}
+
+    /**
+    Returns an owned copy of the structure, which a Map fitting all layers keeps to draw it again.
+
+    Structures which do not implement it are drawn on fitted Maps without the canvas being fitted to them.
+     */
+    fn snapshot(&self) -> Option<map::Snapshot> {
+        None
+    }
 }
 
 /**
@@ -32,21 +48,21 @@ pub trait Location {
     /**
     Retrieves the X coordinate.
      */
-    fn x(&self) -> i16 {
+    fn x(&self) -> i32 {
         self.position().x
     }
 
     /**
     Retrieves the Y coordinate.
      */
-    fn y(&self) -> i16 {
+    fn y(&self) -> i32 {
         self.position().y
     }
 
     /**
     Returns the sum of the x and y value.
      */
-    fn sum(&self) -> i16 {
+    fn sum(&self) -> i32 {
         self.x() + self.y()
     }
 }
@@ -76,3 +92,71 @@ pub trait Find: Hash + Location {
 pub trait Hash {
     fn hash(&self) -> u64;
 }
+
+/**
+A shape supplied by the caller, drawn with Shape::Custom.
+
+The position is the top-left corner of the node, and the size its radius in pixels.
+Shapes are shared by the Nodes using them, which can be sent between threads, so they are Send and Sync.
+
+
+## Examples
+
+```
+# use pathtracer::*;
+# use std::sync::Arc;
+#[derive(Debug)]
+struct Cross;
+
+impl CustomShape for Cross {
+    fn area(&self, size: u32) -> Vec<Coordinate> {
+        let size = size as i32;
+        (0..size)
+            .flat_map(|i| vec![Coordinate::new(i, size / 2), Coordinate::new(size / 2, i)])
+            .collect()
+    }
+}
+
+let mut nodes = Node::from_list(&[(0, 0), (10, 10)]);
+for node in nodes.iter_mut() {
+    node.color = Some(image::Rgba([255, 0, 0, 255]));
+}
+let image = Map::new()
+    .map_shape(&nodes, &Shape::Custom(Arc::new(Cross)))
+    .consume();
+assert!(image.image().pixels().any(|p| p.0 == [255, 0, 0, 255]));
+```
+ */
+pub trait CustomShape: std::fmt::Debug + Send + Sync {
+    /**
+    Returns the pixels the shape covers, relative to the position.
+     */
+    fn area(&self, size: u32) -> Vec<Coordinate>;
+
+    /**
+    Draws the shape on the image, by default painting every pixel of the area.
+     */
+    fn draw(&self, image: &mut IW, pos: Coordinate, size: u32, color: image::Rgba<u8>) {
+        image.paint(
+            self.area(size)
+                .into_iter()
+                .map(|o| (pos + o, color))
+                .collect(),
+        );
+    }
+
+    /**
+    Writes the shape to the document, by default as a square for every pixel of the area.
+     */
+    fn draw_svg(
+        &self,
+        svg: &mut map::svg::Svg,
+        pos: Coordinate,
+        size: u32,
+        color: image::Rgba<u8>,
+    ) {
+        for o in self.area(size) {
+            svg.shape(&Shape::Square, pos + o, 1, color);
+        }
+    }
+}